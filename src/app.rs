@@ -32,7 +32,7 @@ impl Component for App {
                 <section class="game-container">
                     <header class="app-header">
                         <h1 class="app-title">{ "Game of Life" }</h1>
-                        <pre>{"Click: toggle cell; CTRL+Click: place Glider; SHIFT+Click: place Pulser"}</pre>
+                        <pre>{"Click: place the selected pattern; CTRL+Click: toggle cell"}</pre>
                     </header>
                 </section>
                 <UniverseModel />
@@ -0,0 +1,243 @@
+// draws a pattern file to an image or an animation from the command line, see `render`
+
+extern crate wasm_gameoflife;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use wasm_gameoflife::formats;
+use wasm_gameoflife::render::animation::{self, AnimationOptions, Crop};
+use wasm_gameoflife::render::svg::{self, Highlight, SvgOptions, HIGHLIGHT_COLOR};
+use wasm_gameoflife::render::{self, Color, RenderOptions};
+use wasm_gameoflife::universe::pattern::PasteMode;
+use wasm_gameoflife::universe::rect::Rect;
+use wasm_gameoflife::universe::Universe;
+
+const USAGE: &str = "usage: export INPUT OUTPUT.png|OUTPUT.gif|OUTPUT.svg [--cell-size N] [--no-grid] \
+[--margin N] [--generations N] [--region X,Y,W,H] [--alive COLOR] [--dead COLOR] [--grid-color COLOR]
+gifs also take [--frames N] [--step N] [--delay MS] [--follow MARGIN]
+svgs also take [--labels N] [--highlight X,Y,W,H]... [--highlight-color COLOR]
+
+reads a pattern in any format the game can load (rle, .cells, life 1.05 or 1.06),
+puts it on a board with a margin of dead cells around it, runs it for N generations
+and draws the board, or just the region given, to OUTPUT.
+a gif is N frames (30 by default), each one --step generations (1 by default) after
+the last and shown for --delay milliseconds (100 by default). --follow crops every
+frame around the pattern's bounding box instead of showing a fixed region.
+the margin is 2 cells by default, plus room for the pattern to travel in a gif.
+an svg can number every Nth row and column and shade any number of regions.
+regions are in board coordinates, where the pattern starts at MARGIN,MARGIN.
+colors are hex, like #CCCCCC";
+
+enum Output {
+    Png,
+    Gif,
+    Svg,
+}
+
+struct Options {
+    input: PathBuf,
+    output: PathBuf,
+    margin: Option<usize>,
+    generations: usize,
+    region: Option<Rect>,
+    follow: Option<usize>,
+    frames: usize,
+    step: usize,
+    delay: usize,
+    labels: Option<usize>,
+    highlights: Vec<Rect>,
+    highlight_color: Color,
+    render: RenderOptions,
+}
+
+fn parse_region(arg: &str, text: &str) -> Result<Rect, String> {
+    let error = || format!("{} needs four numbers, X,Y,W,H", arg);
+    let numbers = text
+        .split(',')
+        .map(|number| number.trim().parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| error())?;
+    match numbers[..] {
+        [x, y, width, height] if width > 0 && height > 0 => Ok(Rect::new(x, y, width, height)),
+        _ => Err(error()),
+    }
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut paths = Vec::new();
+    let mut margin = None;
+    let mut generations = 0;
+    let mut region = None;
+    let mut follow = None;
+    let mut labels = None;
+    let mut highlights = Vec::new();
+    let mut highlight_color = HIGHLIGHT_COLOR;
+    let mut animation = AnimationOptions::default();
+    let mut render = RenderOptions::default();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--cell-size" => {
+                render.cell_size = value()?
+                    .parse()
+                    .ok()
+                    .filter(|&size| size > 0)
+                    .ok_or("--cell-size needs a number above 0")?
+            }
+            "--no-grid" => render.grid = false,
+            "--margin" => margin = Some(value()?.parse().map_err(|_| "--margin needs a number")?),
+            "--generations" => {
+                generations = value()?
+                    .parse()
+                    .map_err(|_| "--generations needs a number")?
+            }
+            "--region" => region = Some(parse_region(&arg, &value()?)?),
+            "--labels" => {
+                labels = Some(
+                    value()?
+                        .parse()
+                        .ok()
+                        .filter(|&every| every > 0)
+                        .ok_or("--labels needs a number above 0")?,
+                )
+            }
+            "--highlight" => highlights.push(parse_region(&arg, &value()?)?),
+            "--highlight-color" => highlight_color = value()?.parse()?,
+            "--follow" => follow = Some(value()?.parse().map_err(|_| "--follow needs a number")?),
+            "--frames" => {
+                animation.frames = value()?
+                    .parse()
+                    .ok()
+                    .filter(|&frames| frames > 0)
+                    .ok_or("--frames needs a number above 0")?
+            }
+            "--step" => {
+                animation.step = value()?
+                    .parse()
+                    .ok()
+                    .filter(|&step| step > 0)
+                    .ok_or("--step needs a number above 0")?
+            }
+            "--delay" => {
+                animation.delay = value()?.parse().map_err(|_| "--delay needs a number")?
+            }
+            "--alive" => render.alive = value()?.parse()?,
+            "--dead" => render.dead = value()?.parse()?,
+            "--grid-color" => render.grid_color = value()?.parse()?,
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown argument {}", arg)),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    match &paths[..] {
+        [input, output] => Ok(Options {
+            input: input.clone(),
+            output: output.clone(),
+            margin,
+            generations,
+            region,
+            follow,
+            frames: animation.frames,
+            step: animation.step,
+            delay: animation.delay,
+            labels,
+            highlights,
+            highlight_color,
+            render,
+        }),
+        _ => Err("needs an INPUT and an OUTPUT".to_string()),
+    }
+}
+
+fn fail(doing: &str, path: &Path, error: impl std::fmt::Display) -> ! {
+    eprintln!("couldn't {} {}: {}", doing, path.display(), error);
+    process::exit(1);
+}
+
+fn main() {
+    let options = parse_options().unwrap_or_else(|error| {
+        eprintln!("{}\n\n{}", error, USAGE);
+        process::exit(2);
+    });
+
+    let text = fs::read_to_string(&options.input)
+        .unwrap_or_else(|error| fail("read", &options.input, error));
+    let loaded =
+        formats::load_pattern(&text).unwrap_or_else(|error| fail("load", &options.input, error));
+
+    let extension = options
+        .output
+        .extension()
+        .and_then(|extension| extension.to_str());
+    let output = match extension {
+        Some("png") => Output::Png,
+        Some("gif") => Output::Gif,
+        Some("svg") => Output::Svg,
+        _ => fail(
+            "write",
+            &options.output,
+            "only .png, .gif and .svg are supported",
+        ),
+    };
+
+    // nothing in life travels faster than c/2, so that's enough room for a spaceship
+    // not to wrap around the board while it's being animated
+    let travel = match output {
+        Output::Gif => (options.frames - 1) * options.step / 2,
+        _ => 0,
+    };
+    let margin = options.margin.unwrap_or(2 + travel);
+
+    let pattern = &loaded.pattern;
+    let mut universe = Universe::new(pattern.width() + margin * 2, pattern.height() + margin * 2);
+    universe.paste(pattern, (margin, margin), PasteMode::Copy);
+    universe.step_by(options.generations);
+
+    let region = options
+        .region
+        .unwrap_or_else(|| Rect::new(0, 0, universe.width(), universe.height()));
+    let bytes = match output {
+        Output::Png => render::render(&universe, region, &options.render).to_png(),
+        Output::Gif => {
+            let crop = match options.follow {
+                Some(margin) => Crop::Follow { margin },
+                None => Crop::Region(region),
+            };
+            let animation = AnimationOptions {
+                frames: options.frames,
+                step: options.step,
+                delay: options.delay,
+                crop,
+                render: options.render,
+            };
+            animation::animate(&universe, &animation)
+                .unwrap_or_else(|error| fail("write", &options.output, error))
+        }
+        Output::Svg => {
+            let svg_options = SvgOptions {
+                render: options.render,
+                labels: options.labels,
+                highlights: options
+                    .highlights
+                    .iter()
+                    .map(|&region| Highlight {
+                        region,
+                        color: options.highlight_color,
+                    })
+                    .collect(),
+            };
+            svg::to_svg(&universe, region, &svg_options).into_bytes()
+        }
+    };
+
+    fs::write(&options.output, bytes).unwrap_or_else(|error| fail("write", &options.output, error));
+    eprintln!("wrote {}", options.output.display());
+}
@@ -0,0 +1,117 @@
+// runs a soup search from the command line, see `search::SoupSearch`
+
+extern crate wasm_gameoflife;
+
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use wasm_gameoflife::search::SoupSearch;
+use wasm_gameoflife::universe::soup::Symmetry;
+
+const USAGE: &str = "usage: soup-search [--soups N] [--prefix SEED] [--symmetry C1] \
+[--checkpoint FILE] [--every N]
+
+runs N soups (1000 by default) and prints a census of what they settled into.
+with --checkpoint the search is saved to FILE every N soups (100 by default),
+and picked back up from it if FILE already exists";
+
+struct Options {
+    soups: usize,
+    prefix: Option<String>,
+    symmetry: Symmetry,
+    checkpoint: Option<PathBuf>,
+    every: usize,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        soups: 1000,
+        prefix: None,
+        symmetry: Symmetry::default(),
+        checkpoint: None,
+        every: 100,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--soups" => options.soups = value()?.parse().map_err(|_| "--soups needs a number")?,
+            "--prefix" => options.prefix = Some(value()?),
+            "--symmetry" => options.symmetry = value()?.parse()?,
+            "--checkpoint" => options.checkpoint = Some(PathBuf::from(value()?)),
+            "--every" => {
+                options.every = value()?
+                    .parse()
+                    .ok()
+                    .filter(|&every| every > 0)
+                    .ok_or("--every needs a number above 0")?
+            }
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+    Ok(options)
+}
+
+fn main() {
+    let options = parse_options().unwrap_or_else(|error| {
+        eprintln!("{}\n\n{}", error, USAGE);
+        process::exit(2);
+    });
+
+    let mut search = match options.checkpoint.as_ref().filter(|path| path.exists()) {
+        Some(path) => {
+            let text = fs::read_to_string(path).unwrap_or_else(|error| {
+                eprintln!("couldn't read {}: {}", path.display(), error);
+                process::exit(1);
+            });
+            let search = SoupSearch::from_checkpoint(&text).unwrap_or_else(|error| {
+                eprintln!("couldn't read {}: {}", path.display(), error);
+                process::exit(1);
+            });
+            eprintln!(
+                "resuming {} from {} after {} soups",
+                search.prefix(),
+                path.display(),
+                search.soups()
+            );
+            search
+        }
+        None => {
+            let prefix = options
+                .prefix
+                .clone()
+                .unwrap_or_else(|| format!("k_{:08x}_", rand::random::<u32>()));
+            SoupSearch::new(&prefix, options.symmetry).unwrap_or_else(|error| {
+                eprintln!("{}\n\n{}", error, USAGE);
+                process::exit(2);
+            })
+        }
+    };
+
+    let mut remaining = options.soups;
+    while remaining > 0 {
+        let batch = remaining.min(options.every);
+        search.run(batch);
+        remaining -= batch;
+
+        if let Some(path) = options.checkpoint.as_ref() {
+            // write next to the checkpoint first so a crash can't leave half of one
+            let partial = path.with_extension("partial");
+            let saved =
+                fs::write(&partial, search.checkpoint()).and_then(|_| fs::rename(&partial, path));
+            if let Err(error) = saved {
+                eprintln!("couldn't save {}: {}", path.display(), error);
+                process::exit(1);
+            }
+        }
+        eprintln!("{} soups", search.soups());
+    }
+
+    print!("{}", search.report());
+}
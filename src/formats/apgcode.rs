@@ -0,0 +1,222 @@
+// apgcodes, the object names used by apgsearch and Catagolue,
+// see https://conwaylife.com/wiki/Apgcode
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::FormatError;
+use crate::universe::census::ObjectKind;
+use crate::universe::pattern::{Pattern, Transform};
+
+/// the digits of the extended Wechsler format, one per 5 cell column
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// the longest run of empty columns a single `y` can stand for
+const MAX_ZERO_RUN: usize = 39;
+
+/// An object's name: a prefix saying what it is, then its extended Wechsler body.
+/// still lifes are `xs` and their population, oscillators `xp` and spaceships `xq`
+/// and their period, for example `xs4_33` (block), `xp2_7` (blinker), `xq4_153` (glider)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Apgcode {
+    kind: ObjectKind,
+    number: usize,
+    body: String,
+}
+
+impl Apgcode {
+    /// names the object with these `phases`, one full period of them starting
+    /// anywhere. `moved` says the object ends the period somewhere else.
+    /// panics if there are no phases
+    pub fn from_phases(phases: &[Pattern], moved: bool) -> Apgcode {
+        assert!(!phases.is_empty(), "an object needs at least one phase");
+        let body = canonical_wechsler(phases);
+        let (kind, number) = if moved {
+            (ObjectKind::Spaceship, phases.len())
+        } else if phases.len() == 1 {
+            (ObjectKind::StillLife, phases[0].population())
+        } else {
+            (ObjectKind::Oscillator, phases.len())
+        };
+        Apgcode { kind, number, body }
+    }
+
+    /// a still life, oscillator or spaceship
+    pub fn kind(&self) -> ObjectKind {
+        self.kind
+    }
+
+    pub fn period(&self) -> usize {
+        match self.kind {
+            ObjectKind::StillLife => 1,
+            _ => self.number,
+        }
+    }
+
+    /// the population of a still life, `None` for anything else
+    pub fn population(&self) -> Option<usize> {
+        match self.kind {
+            ObjectKind::StillLife => Some(self.number),
+            _ => None,
+        }
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// the object in its canonical phase and orientation
+    pub fn pattern(&self) -> Pattern {
+        decode(&self.body).expect("body to have been checked")
+    }
+}
+
+impl fmt::Display for Apgcode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let prefix = match self.kind {
+            ObjectKind::StillLife => "xs",
+            ObjectKind::Oscillator => "xp",
+            _ => "xq",
+        };
+        write!(f, "{}{}_{}", prefix, self.number, self.body)
+    }
+}
+
+impl FromStr for Apgcode {
+    type Err = FormatError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        let invalid = || FormatError::InvalidHeader(code.to_string());
+        let (prefix, body) = code.split_once('_').ok_or_else(invalid)?;
+        let kind = match prefix.get(..2) {
+            Some("xs") => ObjectKind::StillLife,
+            Some("xp") => ObjectKind::Oscillator,
+            Some("xq") => ObjectKind::Spaceship,
+            _ => return Err(invalid()),
+        };
+        let number: usize = prefix[2..].parse().map_err(|_| invalid())?;
+        if number == 0 && kind != ObjectKind::StillLife {
+            return Err(invalid());
+        }
+
+        // make sure the body can be read, so `pattern` can't fail
+        let pattern = decode(body)?;
+        if kind == ObjectKind::StillLife && pattern.population() != number {
+            return Err(invalid());
+        }
+
+        Ok(Apgcode {
+            kind,
+            number,
+            body: body.to_string(),
+        })
+    }
+}
+
+/// reads the pattern an apgcode names, like `rle::read`
+pub fn read(code: &str) -> Result<Pattern, FormatError> {
+    Ok(code.trim().parse::<Apgcode>()?.pattern())
+}
+
+/// reads an extended Wechsler body back into a pattern, see `wechsler`
+pub fn decode(body: &str) -> Result<Pattern, FormatError> {
+    let mut cells = vec![];
+    let (mut x, mut y) = (0, 0);
+    let mut characters = body.chars();
+
+    while let Some(character) = characters.next() {
+        match character {
+            'w' => x += 2,
+            'x' => x += 3,
+            'y' => {
+                let run = characters
+                    .next()
+                    .filter(|count| !count.is_ascii_uppercase())
+                    .and_then(|count| count.to_digit(36))
+                    .ok_or(FormatError::UnexpectedEnd { line: 1 })?;
+                x += 4 + run as usize;
+            }
+            'z' => {
+                x = 0;
+                y += 5;
+            }
+            _ => {
+                let column = character
+                    .to_digit(32)
+                    .filter(|_| !character.is_ascii_uppercase())
+                    .ok_or(FormatError::UnexpectedCharacter { character, line: 1 })?;
+                for row in 0..5 {
+                    if column & 1 << row != 0 {
+                        cells.push((x, y + row));
+                    }
+                }
+                x += 1;
+            }
+        }
+    }
+
+    Ok(Pattern::from_cells(&cells))
+}
+
+/// the extended Wechsler body of `pattern` as it is, without choosing an orientation.
+///
+/// the pattern is cut into strips 5 rows tall, separated by `z`. each column of a
+/// strip is a digit with the top row in the lowest bit, and runs of empty
+/// columns are shortened to `0`, `w`, `x` or `y` and a count
+pub fn wechsler(pattern: &Pattern) -> String {
+    let mut code = String::new();
+    let strips = pattern.height().div_ceil(5);
+
+    for strip in 0..strips {
+        if strip != 0 {
+            code.push('z');
+        }
+
+        let mut zeros = 0;
+        for x in 0..pattern.width() {
+            let column = (0..5)
+                .filter(|row| pattern.get_cell(x, strip * 5 + row))
+                .fold(0, |column, row| column | 1 << row);
+            if column == 0 {
+                zeros += 1;
+                continue;
+            }
+            push_zeros(&mut code, zeros);
+            zeros = 0;
+            code.push(DIGITS[column] as char);
+        }
+    }
+
+    code
+}
+
+/// the extended Wechsler body Catagolue would name an object with `phases`:
+/// the shortest, then alphabetically first, body over every phase and orientation
+pub fn canonical_wechsler(phases: &[Pattern]) -> String {
+    phases
+        .iter()
+        .flat_map(|phase| {
+            let trimmed = phase.trimmed();
+            Transform::ALL
+                .iter()
+                .map(move |&transform| wechsler(&trimmed.transform(transform)))
+        })
+        .min_by(|a, b| (a.len(), a).cmp(&(b.len(), b)))
+        .unwrap_or_default()
+}
+
+fn push_zeros(code: &mut String, mut zeros: usize) {
+    while zeros > 0 {
+        let run = zeros.min(MAX_ZERO_RUN);
+        match run {
+            1 => code.push('0'),
+            2 => code.push('w'),
+            3 => code.push('x'),
+            _ => {
+                code.push('y');
+                code.push(DIGITS[run - 4] as char);
+            }
+        }
+        zeros -= run;
+    }
+}
@@ -0,0 +1,106 @@
+// Life 1.05, blocks of plaintext rows at given offsets,
+// see https://conwaylife.com/wiki/Life_1.05
+
+use std::fmt::Write;
+
+use super::{offset, parse_coordinate, FormatError, LoadedPattern};
+use crate::universe::pattern::Pattern;
+
+pub const HEADER: &str = "#Life 1.05";
+
+/// rows longer than this are split into blocks side by side, as the format asks
+const MAX_LINE_LENGTH: usize = 80;
+
+/// reads a Life 1.05 pattern. each `#P x y` line starts a block of rows, `.` for
+/// dead cells and `*` for live ones, with its top left at `(x, y)`. only the normal
+/// Life rule (`#N`, or `#R 23/3`) can be read
+pub fn read(text: &str) -> Result<LoadedPattern, FormatError> {
+    let mut cells = vec![];
+    let (mut left, mut top, mut row) = (0isize, 0isize, 0usize);
+
+    for (number, line) in text.lines().enumerate() {
+        let number = number + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("#Life") || line.starts_with("#D") || line == "#N" {
+            continue;
+        }
+
+        if let Some(rule) = line.strip_prefix("#R") {
+            if rule.trim() != "23/3" {
+                return Err(FormatError::InvalidHeader(line.to_string()));
+            }
+        } else if let Some(position) = line.strip_prefix("#P") {
+            let mut words = position.split_whitespace();
+            let mut coordinate = || {
+                words
+                    .next()
+                    .ok_or(FormatError::UnexpectedEnd { line: number })
+                    .and_then(|word| parse_coordinate(word, number))
+            };
+            left = coordinate()?;
+            top = coordinate()?;
+            row = 0;
+        } else if line.starts_with('#') {
+            return Err(FormatError::InvalidHeader(line.to_string()));
+        } else {
+            for (x, character) in line.chars().enumerate() {
+                match character {
+                    '.' => {}
+                    '*' => match (offset(left, x), offset(top, row)) {
+                        (Some(x), Some(y)) => cells.push((x, y)),
+                        (x, y) => {
+                            return Err(FormatError::OutOfRange {
+                                x: x.unwrap_or(isize::MAX),
+                                y: y.unwrap_or(isize::MAX),
+                            })
+                        }
+                    },
+                    _ => {
+                        return Err(FormatError::UnexpectedCharacter {
+                            character,
+                            line: number,
+                        })
+                    }
+                }
+            }
+            row += 1;
+        }
+    }
+
+    LoadedPattern::from_signed(&cells)
+}
+
+/// writes a pattern as Life 1.05, its top left at `(0, 0)`
+pub fn write(pattern: &Pattern) -> String {
+    let mut out = format!("{}\n#N\n", HEADER);
+
+    for left in (0..pattern.width()).step_by(MAX_LINE_LENGTH) {
+        let right = (left + MAX_LINE_LENGTH).min(pattern.width());
+        let cells: Vec<_> = pattern
+            .cells()
+            .iter()
+            .filter(|&&(x, _)| x >= left && x < right)
+            .collect();
+        let (top, bottom) = match (cells.first(), cells.last()) {
+            (Some(&&(_, top)), Some(&&(_, bottom))) => (top, bottom),
+            _ => continue,
+        };
+
+        writeln!(out, "#P {} {}", left, top).unwrap();
+        let mut cells = cells.into_iter().peekable();
+        for y in top..=bottom {
+            let mut row = vec!['.'; right - left];
+            while let Some(&&(x, _)) = cells.peek().filter(|&&&(_, cy)| cy == y) {
+                row[x - left] = '*';
+                cells.next();
+            }
+            // an empty row is written as a single dead cell
+            let row: String = row.into_iter().collect();
+            let row = row.trim_end_matches('.');
+            out.push_str(if row.is_empty() { "." } else { row });
+            out.push('\n');
+        }
+    }
+
+    out
+}
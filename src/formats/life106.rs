@@ -0,0 +1,43 @@
+// Life 1.06, a list of live cell coordinates, see https://conwaylife.com/wiki/Life_1.06
+
+use std::fmt::Write;
+
+use super::{parse_coordinate, FormatError, LoadedPattern};
+use crate::universe::pattern::Pattern;
+
+pub const HEADER: &str = "#Life 1.06";
+
+/// reads a Life 1.06 pattern: the header, then an `x y` line for each live cell.
+/// coordinates can be negative, the pattern's top left ends up at `origin`
+pub fn read(text: &str) -> Result<LoadedPattern, FormatError> {
+    let mut cells = vec![];
+
+    for (number, line) in text.lines().enumerate() {
+        let number = number + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut words = line.split_whitespace();
+        let mut coordinate = || {
+            words
+                .next()
+                .ok_or(FormatError::UnexpectedEnd { line: number })
+                .and_then(|word| parse_coordinate(word, number))
+        };
+        let (x, y) = (coordinate()?, coordinate()?);
+        cells.push((x, y));
+    }
+
+    LoadedPattern::from_signed(&cells)
+}
+
+/// writes a pattern as Life 1.06, with its top left cell at `(0, 0)`
+pub fn write(pattern: &Pattern) -> String {
+    let mut out = format!("{}\n", HEADER);
+    for &(x, y) in pattern.cells() {
+        writeln!(out, "{} {}", x, y).unwrap();
+    }
+    out
+}
@@ -0,0 +1,174 @@
+// reading and writing patterns in the file formats used by other Life programs
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::universe::pattern::Pattern;
+
+pub mod apgcode;
+pub mod life105;
+pub mod life106;
+pub mod plaintext;
+pub mod rle;
+
+/// Why a pattern file couldn't be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatError {
+    /// the header line is missing something or can't be read
+    InvalidHeader(String),
+    /// a character that doesn't belong in the format, and the (1 based) line it is on
+    UnexpectedCharacter { character: char, line: usize },
+    /// a number too big to fit in a `usize`, and the line it is on
+    NumberTooLarge { line: usize },
+    /// the text stopped partway through something, and the line it stopped on
+    UnexpectedEnd { line: usize },
+    /// base64 or compressed data that can't be decoded, and why
+    InvalidEncoding(String),
+    /// a cell that would land at `(x, y)`, off the board it's being loaded into or
+    /// too far from the rest of the pattern to fit in one
+    OutOfRange { x: isize, y: isize },
+    /// a line that can't be read, its (1 based) number and what it says
+    InvalidLine { line: usize, text: String },
+    /// a board bigger than `universe::MAX_SIZE` on a side
+    TooLarge { width: usize, height: usize },
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormatError::InvalidHeader(header) => write!(f, "invalid header: {}", header),
+            FormatError::UnexpectedCharacter { character, line } => {
+                write!(f, "unexpected character {:?} on line {}", character, line)
+            }
+            FormatError::NumberTooLarge { line } => write!(f, "number too large on line {}", line),
+            FormatError::UnexpectedEnd { line } => write!(f, "unexpected end on line {}", line),
+            FormatError::InvalidEncoding(reason) => write!(f, "invalid encoding: {}", reason),
+            FormatError::OutOfRange { x, y } => write!(f, "cell ({}, {}) is off the board", x, y),
+            FormatError::InvalidLine { line, text } => {
+                write!(f, "invalid line {}: {}", line, text)
+            }
+            FormatError::TooLarge { width, height } => {
+                write!(f, "a {}x{} board is too large", width, height)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+/// The pattern file formats that can be read and written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Rle,
+    /// `.cells`
+    Plaintext,
+    Life105,
+    Life106,
+}
+
+impl Format {
+    /// guesses the format of `text` from its first line
+    pub fn sniff(text: &str) -> Format {
+        let first = text.lines().map(str::trim).find(|line| !line.is_empty());
+        match first {
+            Some(line) if line.starts_with(life105::HEADER) => Format::Life105,
+            Some(line) if line.starts_with(life106::HEADER) => Format::Life106,
+            Some(line) if line.starts_with('!') => Format::Plaintext,
+            Some(line) if line.chars().all(|c| ".O*".contains(c)) => Format::Plaintext,
+            // Life 1.06 without its header
+            Some(line)
+                if line
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || c == '-' || c == ' ') =>
+            {
+                let numbers = line.split_whitespace().count();
+                if numbers == 2 {
+                    Format::Life106
+                } else {
+                    Format::Rle
+                }
+            }
+            _ => Format::Rle,
+        }
+    }
+
+    pub fn read(self, text: &str) -> Result<LoadedPattern, FormatError> {
+        match self {
+            Format::Rle => rle::read(text).map(LoadedPattern::from),
+            Format::Plaintext => plaintext::read(text).map(LoadedPattern::from),
+            Format::Life105 => life105::read(text),
+            Format::Life106 => life106::read(text),
+        }
+    }
+
+    pub fn write(self, pattern: &Pattern) -> String {
+        match self {
+            Format::Rle => rle::write(pattern),
+            Format::Plaintext => plaintext::write(pattern),
+            Format::Life105 => life105::write(pattern),
+            Format::Life106 => life106::write(pattern),
+        }
+    }
+}
+
+/// A pattern read from a file, and where the file puts its top left corner.
+/// formats without coordinates put it at `(0, 0)`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LoadedPattern {
+    pub pattern: Pattern,
+    pub origin: (isize, isize),
+}
+
+impl LoadedPattern {
+    /// the smallest pattern holding `cells`, which can be anywhere as long as
+    /// they're no more than `isize::MAX` apart
+    fn from_signed(cells: &[(isize, isize)]) -> Result<Self, FormatError> {
+        let left = cells.iter().map(|&(x, _)| x).min().unwrap_or(0);
+        let top = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+        let cells = cells
+            .iter()
+            .map(|&(x, y)| match (x.checked_sub(left), y.checked_sub(top)) {
+                (Some(dx), Some(dy)) => Ok((dx as usize, dy as usize)),
+                _ => Err(FormatError::OutOfRange { x, y }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(LoadedPattern {
+            pattern: Pattern::from_cells(&cells),
+            origin: (left, top),
+        })
+    }
+}
+
+impl From<Pattern> for LoadedPattern {
+    fn from(pattern: Pattern) -> Self {
+        LoadedPattern {
+            pattern,
+            origin: (0, 0),
+        }
+    }
+}
+
+/// reads a pattern in any of the supported formats, see `Format::sniff`
+pub fn load_pattern(text: &str) -> Result<LoadedPattern, FormatError> {
+    Format::sniff(text).read(text)
+}
+
+/// `start` moved on by `by`, `None` if that doesn't fit in an `isize`
+pub(crate) fn offset(start: isize, by: usize) -> Option<isize> {
+    isize::try_from(by)
+        .ok()
+        .and_then(|by| start.checked_add(by))
+}
+
+/// reads a signed coordinate from line `line`
+fn parse_coordinate(word: &str, line: usize) -> Result<isize, FormatError> {
+    let digits = word.strip_prefix('-').unwrap_or(word);
+    if let Some(character) = digits.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(FormatError::UnexpectedCharacter { character, line });
+    }
+    if digits.is_empty() {
+        return Err(FormatError::UnexpectedEnd { line });
+    }
+    word.parse()
+        .map_err(|_| FormatError::NumberTooLarge { line })
+}
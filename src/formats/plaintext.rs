@@ -0,0 +1,53 @@
+// plaintext patterns (.cells), see https://conwaylife.com/wiki/Plaintext
+
+use super::FormatError;
+use crate::universe::pattern::Pattern;
+
+/// reads a plaintext pattern: `!` comment lines, then a row per line with `.`
+/// for dead cells and `O` (or `*`) for live ones. short rows are padded with dead cells
+pub fn read(text: &str) -> Result<Pattern, FormatError> {
+    let mut cells = vec![];
+    let (mut width, mut height) = (0, 0);
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        if line.starts_with('!') {
+            continue;
+        }
+
+        for (x, character) in line.chars().enumerate() {
+            match character {
+                '.' => {}
+                'O' | '*' => cells.push((x, height)),
+                _ => {
+                    return Err(FormatError::UnexpectedCharacter {
+                        character,
+                        line: number + 1,
+                    })
+                }
+            }
+        }
+        width = width.max(line.chars().count());
+        height += 1;
+    }
+
+    Ok(Pattern::with_cells(width, height, &cells))
+}
+
+/// writes a pattern as plaintext, every row the full width of the pattern
+pub fn write(pattern: &Pattern) -> String {
+    let mut out = String::new();
+    let mut cells = pattern.cells().iter().peekable();
+
+    for y in 0..pattern.height() {
+        let mut row = vec!['.'; pattern.width()];
+        while let Some(&&(x, _)) = cells.peek().filter(|&&&(_, cy)| cy == y) {
+            row[x] = 'O';
+            cells.next();
+        }
+        out.extend(row);
+        out.push('\n');
+    }
+
+    out
+}
@@ -0,0 +1,213 @@
+// run length encoded patterns, see https://conwaylife.com/wiki/Run_Length_Encoded
+
+use std::convert::TryFrom;
+
+use super::FormatError;
+use crate::universe::pattern::Pattern;
+
+/// the longest line `write` produces, as recommended by the format
+const MAX_LINE_LENGTH: usize = 70;
+
+/// reads an RLE pattern. `#` comment lines are skipped and the header is
+/// optional, without one the pattern grows to fit its cells. cells past the size
+/// in the header are `OutOfRange`.
+/// LifeHistory states are read too, keeping only whether each cell is alive
+pub fn read(text: &str) -> Result<Pattern, FormatError> {
+    decode(text, None)
+}
+
+/// like `read`, but cells at or past `width` x `height` are `OutOfRange` too,
+/// header or not
+pub fn read_within(text: &str, width: usize, height: usize) -> Result<Pattern, FormatError> {
+    decode(text, Some((width, height)))
+}
+
+fn decode(text: &str, limit: Option<(usize, usize)>) -> Result<Pattern, FormatError> {
+    let mut size = None;
+    let mut bounds = limit;
+    let mut cells = vec![];
+    let (mut x, mut y) = (0usize, 0usize);
+    let mut run: Option<usize> = None;
+    let mut started = false;
+
+    'lines: for (number, line) in text.lines().enumerate() {
+        let number = number + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !started && size.is_none() && line.starts_with('x') {
+            let (width, height) = read_header(line)?;
+            size = Some((width, height));
+            bounds = Some(match limit {
+                Some((x, y)) => (width.min(x), height.min(y)),
+                None => (width, height),
+            });
+            continue;
+        }
+        started = true;
+
+        for character in line.chars() {
+            if let Some(digit) = character.to_digit(10) {
+                let n = run
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|n| n.checked_add(digit as usize))
+                    .ok_or(FormatError::NumberTooLarge { line: number })?;
+                run = Some(n);
+                continue;
+            }
+
+            let count = run.take().unwrap_or(1);
+            let too_large = FormatError::NumberTooLarge { line: number };
+            match character {
+                // the even LifeHistory states are dead, the odd ones alive
+                'b' | '.' | 'B' | 'D' | 'F' => x = x.checked_add(count).ok_or(too_large)?,
+                'o' | 'A' | 'C' | 'E' => {
+                    let end = x.checked_add(count).ok_or(too_large)?;
+                    if let Some((width, height)) = bounds {
+                        if y >= height {
+                            return Err(off_board(x, y));
+                        }
+                        if end > width {
+                            return Err(off_board(x.max(width), y));
+                        }
+                    }
+                    cells.extend((x..end).map(|cx| (cx, y)));
+                    x = end;
+                }
+                '$' => {
+                    y = y.checked_add(count).ok_or(too_large)?;
+                    x = 0;
+                }
+                '!' => break 'lines,
+                c if c.is_whitespace() => {}
+                _ => {
+                    return Err(FormatError::UnexpectedCharacter {
+                        character,
+                        line: number,
+                    })
+                }
+            }
+        }
+    }
+
+    let fitted = Pattern::from_cells(&cells);
+    let (width, height) = size.unwrap_or((0, 0));
+    Ok(Pattern::with_cells(
+        width.max(fitted.width()),
+        height.max(fitted.height()),
+        &cells,
+    ))
+}
+
+/// the error for a live cell at `(x, y)`
+fn off_board(x: usize, y: usize) -> FormatError {
+    FormatError::OutOfRange {
+        x: isize::try_from(x).unwrap_or(isize::MAX),
+        y: isize::try_from(y).unwrap_or(isize::MAX),
+    }
+}
+
+/// reads the `x = m, y = n, rule = ...` line
+fn read_header(line: &str) -> Result<(usize, usize), FormatError> {
+    let invalid = || FormatError::InvalidHeader(line.to_string());
+    let (mut width, mut height) = (None, None);
+
+    for field in line.split(',') {
+        let mut parts = field.splitn(2, '=');
+        let key = parts.next().unwrap_or("").trim();
+        let value = parts.next().ok_or_else(invalid)?.trim();
+        match key {
+            "x" => width = Some(value.parse::<usize>().map_err(|_| invalid())?),
+            "y" => height = Some(value.parse::<usize>().map_err(|_| invalid())?),
+            _ => {}
+        }
+    }
+
+    match (width, height) {
+        (Some(width), Some(height)) => Ok((width, height)),
+        _ => Err(invalid()),
+    }
+}
+
+/// writes a pattern as RLE with a header and the Life rule
+pub fn write(pattern: &Pattern) -> String {
+    let cells = pattern.cells().iter().map(|&(x, y)| (x, y, 'o'));
+    encode(pattern.width(), pattern.height(), "B3/S23", 'b', cells)
+}
+
+/// writes a board as LifeHistory RLE: `alive` cells in state 1 (`A`) and the rest
+/// of `envelope`, the cells that used to be alive, in state 2 (`B`)
+pub fn write_history(alive: &Pattern, envelope: &Pattern) -> String {
+    let mut cells: Vec<_> = alive
+        .cells()
+        .iter()
+        .map(|&(x, y)| (x, y, 'A'))
+        .chain(
+            envelope
+                .cells()
+                .iter()
+                .filter(|&&(x, y)| !alive.get_cell(x, y))
+                .map(|&(x, y)| (x, y, 'B')),
+        )
+        .collect();
+    cells.sort_unstable_by_key(|&(x, y, _)| (y, x));
+
+    let width = alive.width().max(envelope.width());
+    let height = alive.height().max(envelope.height());
+    encode(width, height, "LifeHistory", '.', cells.into_iter())
+}
+
+/// run length encodes cells given row by row as `(x, y, tag)`, gaps being `dead`
+fn encode(
+    width: usize,
+    height: usize,
+    rule: &str,
+    dead: char,
+    cells: impl Iterator<Item = (usize, usize, char)>,
+) -> String {
+    let mut tokens: Vec<(usize, char)> = vec![];
+    let mut push = |count: usize, tag: char| {
+        if count == 0 {
+            return;
+        }
+        match tokens.last_mut() {
+            Some((n, last)) if *last == tag => *n += count,
+            _ => tokens.push((count, tag)),
+        }
+    };
+
+    let (mut x, mut y) = (0, 0);
+    for (cx, cy, tag) in cells {
+        if cy > y {
+            push(cy - y, '$');
+            y = cy;
+            x = 0;
+        }
+        push(cx - x, dead);
+        push(1, tag);
+        x = cx + 1;
+    }
+    push(1, '!');
+
+    let mut out = format!("x = {}, y = {}, rule = {}\n", width, height, rule);
+    let mut line = String::new();
+    for (count, tag) in tokens {
+        let token = if count == 1 {
+            tag.to_string()
+        } else {
+            format!("{}{}", count, tag)
+        };
+        if line.len() + token.len() > MAX_LINE_LENGTH {
+            out.push_str(&line);
+            out.push('\n');
+            line.clear();
+        }
+        line.push_str(&token);
+    }
+    out.push_str(&line);
+    out.push('\n');
+
+    out
+}
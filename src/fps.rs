@@ -23,8 +23,8 @@ impl Fps {
             self.frames.remove(0);
         }
 
-        let mut min = std::f64::MAX;
-        let mut max = std::f64::MIN;
+        let mut min = f64::MAX;
+        let mut max = f64::MIN;
         let mut sum = 0f64;
         for frame in &self.frames {
             sum += frame;
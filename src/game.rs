@@ -1,12 +1,23 @@
 // Game container code
 
 use crate::fps;
-use crate::universe::Universe;
+use crate::library::{LibraryPattern, PATTERNS};
+use crate::render::frame::{Frame, Overlay};
+use crate::render::svg::{self, SvgOptions};
+use crate::render::{self, Image, RenderOptions, BIRTH_COLOR, DEATH_COLOR};
+use crate::session::Session;
+use crate::universe::heatmap;
+use crate::universe::node::NodeId;
+use crate::universe::pattern::{PasteMode, Transform};
+use crate::universe::rect::Rect;
+use crate::universe::soup::Symmetry;
+use crate::universe::timeline::Timeline;
+use crate::universe::{Anchor, Universe, MAX_SIZE};
 
 use yew::prelude::*;
 
 //
-use wasm_bindgen::JsCast;
+use wasm_bindgen::{Clamped, JsCast};
 
 pub struct KeysPressed {
     ctrl: bool,
@@ -29,42 +40,102 @@ impl KeysPressed {
     pub fn only_ctrl(&self) -> bool {
         self.ctrl && !self.shift && !self.alt && !self.meta
     }
-    pub fn only_shift(&self) -> bool {
-        !self.ctrl && self.shift && !self.alt && !self.meta
-    }
-    #[allow(dead_code)]
-    pub fn only_alt(&self) -> bool {
-        !self.ctrl && !self.shift && self.alt && !self.meta
-    }
-    #[allow(dead_code)]
-    pub fn only_ctrl_shift(&self) -> bool {
-        self.ctrl && self.shift && !self.alt && !self.meta
-    }
-    #[allow(dead_code)]
-    pub fn only_ctrl_alt(&self) -> bool {
-        self.ctrl && !self.shift && self.alt && !self.meta
-    }
 }
 
 pub enum Msg {
     Random,
+    Soup,
+    SetSeed(String),
+    SetDensity(f64),
+    SetSymmetry(Symmetry),
     Step,
     Reset,
     Click(i32, i32, KeysPressed),
     Tick(f64),
     TickToggle,
     StepsPerTick(usize),
+    Resize(usize, usize),
+    SetBrush(Option<usize>),
+    SetTransform(Transform),
+    SetAnchor(Anchor),
+    Crop,
+    SetCellSize(usize),
+    SelectSlot(usize),
+    Save,
+    Load,
+    ToggleAutosave,
+    Share,
+    ExportPng,
+    ExportSvg,
+    ToggleHistory,
+    ExportHistory,
+    ToggleHeatmap,
+    SetHeatmapWindow(usize),
+    ExportHeatmap,
+    ToggleChanges,
+    ToggleAgeColors,
+    StepBack,
+    Seek(usize),
 }
 
-const CELL_SIZE: usize = 5; // px
-const GRID_COLOR: &str = "#CCCCCC";
-const DEAD_COLOR: &str = "#FFFFFF";
-const ALIVE_COLOR: &str = "#000000";
+const CELL_SIZE: usize = 5; // px, until zoomed
+const MAX_CELL_SIZE: usize = 20;
+
+/// local storage keys are this followed by the slot number
+const STORAGE_PREFIX: &str = "hashlife.slot.";
+const AUTOSAVE_ENABLED_KEY: &str = "hashlife.autosave";
+/// slot 0 is written by autosave
+const SLOTS: [&str; 5] = ["Autosave", "Slot 1", "Slot 2", "Slot 3", "Slot 4"];
+/// how often a running board is autosaved, in milliseconds
+const AUTOSAVE_INTERVAL: f64 = 5000.0;
+
+const ANCHORS: [(&str, Anchor); 5] = [
+    ("Top Left", Anchor::TopLeft),
+    ("Top Right", Anchor::TopRight),
+    ("Bottom Left", Anchor::BottomLeft),
+    ("Bottom Right", Anchor::BottomRight),
+    ("Center", Anchor::Center),
+];
+
+const TRANSFORMS: [(&str, Transform); 8] = [
+    ("As Is", Transform::Identity),
+    ("Rotated 90°", Transform::Rotate90),
+    ("Rotated 180°", Transform::Rotate180),
+    ("Rotated 270°", Transform::Rotate270),
+    ("Flipped Horizontally", Transform::FlipHorizontal),
+    ("Flipped Vertically", Transform::FlipVertical),
+    ("Transposed", Transform::Transpose),
+    ("Anti-Transposed", Transform::AntiTranspose),
+];
 
 pub struct UniverseModel {
     link: ComponentLink<Self>,
     active: bool,
     n_steps: usize,
+    anchor: Anchor,
+    brush: Option<usize>,
+    transform: Transform,
+    seed: String,
+    density: f64,
+    symmetry: Symmetry,
+    cell_size: usize,
+    slot: usize,
+    /// what's in each save slot, for the load menu
+    slot_descriptions: Vec<String>,
+    autosave: bool,
+    last_autosave: f64,
+    /// a link to the board as it was when Share was last pressed
+    share_link: String,
+    /// generations the heatmap covers, kept while it's off
+    heatmap_window: usize,
+    /// the board before the last step, to highlight what changed
+    previous_root: Option<NodeId>,
+    show_changes: bool,
+    color_by_age: bool,
+    /// past boards to scrub back through
+    timeline: Timeline,
+    /// the board as last drawn, repainted where it changes
+    frame: Option<Frame>,
     universe: Universe,
     fps: fps::Fps,
     fps_html: String,
@@ -76,104 +147,105 @@ pub struct UniverseModel {
 
 impl UniverseModel {
     fn step(&mut self) {
+        // catches edits made since the last step before they're stepped past
+        self.timeline.record(&self.universe);
+        self.previous_root = Some(self.universe.root());
         for _ in 0..self.n_steps {
             self.universe.step();
+            self.timeline.record(&self.universe);
         }
     }
 
-    fn draw_game(&mut self) {
-        let canvas = self.canvas.as_ref().expect("canvas not initialised!");
-
-        let width = self.universe.width();
-        let height = self.universe.height();
-
-        canvas.set_height(((CELL_SIZE + 1) * height + 1) as u32);
-        canvas.set_width(((CELL_SIZE + 1) * width + 1) as u32);
-
-        let ctx = self.ctx.as_ref().expect("canvas context not initialise!");
-
-        self.draw_grid(&ctx);
-        self.draw_cells(&ctx);
-        self.fps.render();
-        self.fps_html = self.fps.get_html();
+    /// goes back or forth to `generation`, pausing so the board stays there
+    fn seek(&mut self, generation: Option<usize>) {
+        self.active = false;
+        let before = self.universe.root();
+        let result = match generation {
+            Some(generation) => self.timeline.seek(&mut self.universe, generation),
+            None => self.timeline.step_back(&mut self.universe),
+        };
+        match result {
+            Ok(()) => {
+                self.previous_root = Some(before);
+                log!("Went to generation {}", self.universe.generation());
+            }
+            Err(error) => {
+                log!("Couldn't go there: {}", error);
+            }
+        }
     }
 
-    fn draw_grid(&self, ctx: &web_sys::CanvasRenderingContext2d) {
-        ctx.begin_path();
-        ctx.set_stroke_style(&GRID_COLOR.into());
-
-        let width = self.universe.width();
-        let height = self.universe.height();
-
-        // Vertical lines.
-        for i in 0..=width {
-            ctx.move_to((i * (CELL_SIZE + 1) + 1) as f64, 0 as f64);
-            ctx.line_to(
-                (i * (CELL_SIZE + 1) + 1) as f64,
-                ((CELL_SIZE + 1) * height + 1) as f64,
-            );
+    fn draw_game(&mut self) {
+        // the whole board is on screen, so that's the viewport to keep ages for
+        let board = Rect::new(0, 0, self.universe.width(), self.universe.height());
+        if self.color_by_age && self.universe.ages().map(|ages| ages.viewport()) != Some(board) {
+            self.universe.set_tracking_ages(Some(board));
         }
 
-        // Horizontal lines.
-        for j in 0..=height {
-            ctx.move_to(0 as f64, (j * (CELL_SIZE + 1) + 1) as f64);
-            ctx.line_to(
-                ((CELL_SIZE + 1) * width + 1) as f64,
-                (j * (CELL_SIZE + 1) + 1) as f64,
-            );
+        let options = RenderOptions {
+            cell_size: self.cell_size,
+            color_by_age: self.color_by_age,
+            ..RenderOptions::default()
+        };
+        // a new cell size needs a new background
+        if self
+            .frame
+            .as_ref()
+            .is_none_or(|frame| frame.options() != &options)
+        {
+            self.frame = Some(Frame::new(
+                self.universe.width(),
+                self.universe.height(),
+                options,
+            ));
         }
+        let overlay = self.overlay();
+        let frame = self.frame.as_mut().expect("frame to be made");
+        let mut dirty = frame.update_with_overlay(&self.universe, overlay);
+        let image = frame.image();
+        let whole = Rect::new(0, 0, image.width(), image.height());
 
-        ctx.stroke();
-    }
-
-    fn get_index(&self, row: usize, col: usize) -> usize {
-        self.universe.get_morton(row, col)
+        // setting the size clears the canvas, even to the same size, so only when it changes
+        let canvas = self.canvas.as_ref().expect("canvas not initialised!");
+        if (canvas.width(), canvas.height()) != (image.width() as u32, image.height() as u32) {
+            canvas.set_width(image.width() as u32);
+            canvas.set_height(image.height() as u32);
+            dirty = vec![whole];
+        }
+        let ctx = self.ctx.as_ref().expect("canvas context not initialise!");
+        for rect in dirty {
+            put_image(ctx, image, rect);
+        }
+        self.fps.render();
+        self.fps_html = self.fps.get_html();
     }
 
-    fn draw_cells(&self, ctx: &web_sys::CanvasRenderingContext2d) {
-        let cells = self.universe.get_cells();
-        let width = self.universe.width();
-        let height = self.universe.height();
-
-        ctx.begin_path();
-
-        // alive cells
-        ctx.set_fill_style(&ALIVE_COLOR.into());
-        for row in 0..height {
-            for col in 0..width {
-                let idx = self.get_index(row, col);
-                if !cells[idx] {
-                    continue;
-                }
-
-                ctx.fill_rect(
-                    (col * (CELL_SIZE + 1) + 1) as f64,
-                    (row * (CELL_SIZE + 1) + 1) as f64,
-                    CELL_SIZE as f64,
-                    CELL_SIZE as f64,
-                )
-            }
+    /// what's painted over the board: births and deaths since the last step, when
+    /// they're being highlighted, with the heatmap on top of them
+    fn overlay(&self) -> Overlay {
+        let mut overlay = Overlay::new();
+        // a resize since the last step leaves nothing to compare against
+        let previous = self
+            .previous_root
+            .filter(|_| self.show_changes)
+            .filter(|&previous| {
+                self.universe.get_node(previous).rect().width() == self.universe.size()
+            });
+        if let Some(previous) = previous {
+            let diff = self.universe.diff(previous, self.universe.root());
+            overlay.extend(diff.births().iter().map(|&cell| (cell, BIRTH_COLOR)));
+            overlay.extend(diff.deaths().iter().map(|&cell| (cell, DEATH_COLOR)));
         }
 
-        // dead cells
-        ctx.set_fill_style(&DEAD_COLOR.into());
-        for row in 0..height {
-            for col in 0..width {
-                let idx = self.get_index(row, col);
-                if cells[idx] {
-                    continue;
-                }
-
-                ctx.fill_rect(
-                    (col * (CELL_SIZE + 1) + 1) as f64,
-                    (row * (CELL_SIZE + 1) + 1) as f64,
-                    CELL_SIZE as f64,
-                    CELL_SIZE as f64,
-                )
+        if let Some(heatmap) = self.universe.activity() {
+            let max = heatmap.max();
+            for (x, y, count) in heatmap.active_cells() {
+                let heat = render::heat_color(count, max);
+                let color = overlay.get(&(x, y)).map_or(heat, |&below| heat.over(below));
+                overlay.insert((x, y), color);
             }
         }
-
-        ctx.stroke();
+        overlay
     }
 
     fn render_loop(&mut self) {
@@ -186,37 +258,176 @@ impl UniverseModel {
         self.render_handle = Some(Box::new(handle));
     }
 
-    fn get_row_col_client_cords(&self, x: i32, y: i32) -> (usize, usize) {
+    /// the `(x, y)` cell under a point in client coordinates
+    fn get_cell_client_cords(&self, x: i32, y: i32) -> (usize, usize) {
         let canvas = self.canvas.as_ref().expect("canvas not initialised!");
         let bounding_rect = canvas.get_bounding_client_rect();
 
         let scale_x = canvas.width() as f64 / bounding_rect.width();
         let scale_y = canvas.height() as f64 / bounding_rect.height();
 
+        let cell_size = self.cell_size;
         let canvas_left = (x as f64 - bounding_rect.left()) * scale_x;
         let canvas_top = (y as f64 - bounding_rect.top()) * scale_y;
-        let row = (canvas_top / (CELL_SIZE + 1) as f64)
+        let cell_x = (canvas_left / (cell_size + 1) as f64)
             .floor()
-            .min((self.universe.height() - 1) as f64);
-        let col = (canvas_left / (CELL_SIZE + 1) as f64)
+            .clamp(0.0, (self.universe.width() - 1) as f64);
+        let cell_y = (canvas_top / (cell_size + 1) as f64)
             .floor()
-            .min((self.universe.width() - 1) as f64);
+            .clamp(0.0, (self.universe.height() - 1) as f64);
+
+        (cell_x as usize, cell_y as usize)
+    }
 
-        (row as usize, col as usize)
+    fn save_to(&mut self, slot: usize) {
+        let session = Session::capture(&self.universe, self.cell_size, self.n_steps);
+        let saved = storage().map(|storage| storage.set_item(&slot_key(slot), &session.to_text()));
+        if !matches!(saved, Some(Ok(()))) {
+            log!("Couldn't save to {}", SLOTS[slot]);
+        }
+        self.describe_slots();
+    }
+
+    fn load_from(&mut self, slot: usize) -> bool {
+        let text =
+            match storage().and_then(|storage| storage.get_item(&slot_key(slot)).ok().flatten()) {
+                Some(text) => text,
+                None => return false,
+            };
+        match Session::from_text(&text) {
+            Ok(session) => {
+                self.restore(session);
+                true
+            }
+            Err(error) => {
+                log!("Couldn't load {}: {}", SLOTS[slot], error);
+                false
+            }
+        }
+    }
+
+    fn restore(&mut self, session: Session) {
+        self.universe = session.universe();
+        // the nodes drawn and compared against belonged to the old universe
+        self.previous_root = None;
+        self.timeline.clear();
+        if let Some(frame) = self.frame.as_mut() {
+            frame.invalidate();
+        }
+        self.cell_size = session.cell_size.min(MAX_CELL_SIZE);
+        self.n_steps = session.steps_per_tick;
+    }
+
+    /// autosaves after anything that changes the board or how it's shown, and
+    /// keeps edits on the timeline
+    fn changed(&mut self) {
+        self.timeline.record(&self.universe);
+        if self.autosave {
+            self.save_to(0);
+        }
+    }
+
+    fn describe_slots(&mut self) {
+        self.slot_descriptions = (0..SLOTS.len())
+            .map(|slot| {
+                let saved = storage()
+                    .and_then(|storage| storage.get_item(&slot_key(slot)).ok().flatten())
+                    .and_then(|text| Session::from_text(&text).ok());
+                match saved {
+                    Some(session) => format!(
+                        "{} ({}x{}, generation {})",
+                        SLOTS[slot], session.width, session.height, session.generation
+                    ),
+                    None => format!("{} (empty)", SLOTS[slot]),
+                }
+            })
+            .collect();
+    }
+
+    fn brush(&self) -> Option<&'static LibraryPattern> {
+        self.brush.and_then(|index| PATTERNS.get(index))
     }
 
     fn process_context_click(&mut self, x: i32, y: i32, keys: KeysPressed) {
-        let (row, col) = self.get_row_col_client_cords(x, y);
-        if keys.only_ctrl() {
-            self.universe.set_flyer(row, col);
-        } else if keys.only_shift() {
-            self.universe.set_pulsar(row, col);
-        } else {
-            self.universe.toggle_cell(row, col);
+        let (cell_x, cell_y) = self.get_cell_client_cords(x, y);
+        match self.brush() {
+            Some(entry) if !keys.only_ctrl() => {
+                // centre the pattern on the clicked cell
+                let pattern = entry.pattern().transform(self.transform);
+                let at = (
+                    cell_x.saturating_sub(pattern.width() / 2),
+                    cell_y.saturating_sub(pattern.height() / 2),
+                );
+                self.universe.paste(&pattern, at, PasteMode::Or);
+            }
+            _ => self.universe.toggle_cell(cell_x, cell_y),
+        }
+    }
+
+    fn brush_description(&self) -> String {
+        match self.brush() {
+            Some(entry) => {
+                let period = match entry.period {
+                    Some(period) => format!("period {}", period),
+                    None => "never settles into a cycle".to_string(),
+                };
+                let speed = match entry.speed() {
+                    Some(speed) => format!(", moves at {}", speed),
+                    None => String::new(),
+                };
+                format!(
+                    "{}: {}x{}, {}{}",
+                    entry.name, entry.width, entry.height, period, speed
+                )
+            }
+            None => "Click toggles a single cell".to_string(),
         }
     }
 }
 
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+fn slot_key(slot: usize) -> String {
+    format!("{}{}", STORAGE_PREFIX, slot)
+}
+
+/// copies `rect` of `image` onto the canvas at the same place
+fn put_image(ctx: &web_sys::CanvasRenderingContext2d, image: &Image, rect: Rect) {
+    let pixels = image.region_pixels(rect);
+    let data = web_sys::ImageData::new_with_u8_clamped_array_and_sh(
+        Clamped(&pixels),
+        rect.width() as u32,
+        rect.height() as u32,
+    );
+    if let Ok(data) = data {
+        let _ = ctx.put_image_data(&data, rect.x() as f64, rect.y() as f64);
+    }
+}
+
+/// hands `bytes` to the browser as a file download, through a data url on a throwaway link
+fn download(filename: &str, mime: &str, bytes: &[u8]) {
+    use base64::Engine;
+
+    let document = match web_sys::window().and_then(|window| window.document()) {
+        Some(document) => document,
+        None => return,
+    };
+    let link = match document
+        .create_element("a")
+        .ok()
+        .and_then(|element| element.dyn_into::<web_sys::HtmlAnchorElement>().ok())
+    {
+        Some(link) => link,
+        None => return,
+    };
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+    link.set_href(&format!("data:{};base64,{}", mime, encoded));
+    link.set_download(filename);
+    link.click();
+}
+
 impl Component for UniverseModel {
     type Message = Msg;
     type Properties = ();
@@ -224,10 +435,28 @@ impl Component for UniverseModel {
     fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
         log!("universe created!");
 
-        UniverseModel {
+        let mut model = UniverseModel {
             link,
             active: false,
             n_steps: 1,
+            anchor: Anchor::default(),
+            brush: None,
+            transform: Transform::default(),
+            seed: String::new(),
+            density: 0.5,
+            symmetry: Symmetry::default(),
+            cell_size: CELL_SIZE,
+            slot: 1,
+            slot_descriptions: vec![],
+            autosave: false,
+            last_autosave: 0.0,
+            share_link: String::new(),
+            heatmap_window: heatmap::DEFAULT_WINDOW,
+            previous_root: None,
+            show_changes: false,
+            color_by_age: false,
+            timeline: Timeline::default(),
+            frame: None,
             universe: Universe::new(256, 256),
             fps: fps::Fps::default(),
             fps_html: String::default(),
@@ -235,23 +464,65 @@ impl Component for UniverseModel {
             canvas: None,
             ctx: None,
             render_handle: None,
+        };
+
+        // open a shared link, or pick up where the last visit left off
+        model.autosave = storage()
+            .and_then(|storage| storage.get_item(AUTOSAVE_ENABLED_KEY).ok().flatten())
+            == Some("true".to_string());
+        let hash = web_sys::window().and_then(|window| window.location().hash().ok());
+        match hash.filter(|hash| hash.len() > 1) {
+            Some(hash) => match Session::from_fragment(&hash) {
+                Ok(session) => model.restore(session),
+                Err(error) => {
+                    log!("Couldn't open the shared link: {}", error);
+                }
+            },
+            None if model.autosave => {
+                model.load_from(0);
+            }
+            None => {}
         }
+        model.describe_slots();
+        model
     }
 
     fn update(&mut self, msg: Self::Message) -> bool {
         match msg {
             Msg::Random => {
-                self.universe.randomize();
-                log!("Random");
+                // a fresh seed, shown so the soup can be made again
+                self.seed = format!("{:08x}", rand::random::<u32>());
+                self.link.send_message(Msg::Soup);
+                true
+            }
+            Msg::Soup => {
+                self.universe
+                    .randomize_with(&self.seed, self.density, None, self.symmetry);
+                log!("Soup {} ({})", self.seed, self.symmetry);
+                self.changed();
                 false
             }
+            Msg::SetSeed(seed) => {
+                self.seed = seed;
+                true
+            }
+            Msg::SetDensity(density) => {
+                self.density = density;
+                true
+            }
+            Msg::SetSymmetry(symmetry) => {
+                self.symmetry = symmetry;
+                true
+            }
             Msg::Step => {
                 self.step();
+                self.changed();
                 false
             }
             Msg::Reset => {
                 self.universe.reset();
                 log!("Reset");
+                self.changed();
                 false
             }
             Msg::TickToggle => {
@@ -261,16 +532,22 @@ impl Component for UniverseModel {
                 } else {
                     self.active = false;
                     log!("Stop");
+                    self.changed();
                 }
                 true
             }
             Msg::Click(x, y, keys) => {
                 self.process_context_click(x, y, keys);
+                self.changed();
                 false
             }
-            Msg::Tick(_) => {
+            Msg::Tick(time) => {
                 if self.active {
                     self.step();
+                    if time - self.last_autosave > AUTOSAVE_INTERVAL {
+                        self.last_autosave = time;
+                        self.changed();
+                    }
                 }
                 self.render_loop();
                 true
@@ -278,6 +555,166 @@ impl Component for UniverseModel {
             Msg::StepsPerTick(n) => {
                 self.n_steps = n;
                 log!("Steps per tick is now: {}", n);
+                self.changed();
+                true
+            }
+            Msg::Resize(width, height) => {
+                let (width, height) = (width.clamp(1, MAX_SIZE), height.clamp(1, MAX_SIZE));
+                self.universe.resize(width, height, self.anchor);
+                log!("Resized to {}x{}", width, height);
+                self.changed();
+                true
+            }
+            Msg::SetBrush(brush) => {
+                self.brush = brush;
+                true
+            }
+            Msg::SetTransform(transform) => {
+                self.transform = transform;
+                true
+            }
+            Msg::SetAnchor(anchor) => {
+                self.anchor = anchor;
+                true
+            }
+            Msg::Crop => {
+                self.universe.crop_to_bounding_box();
+                log!(
+                    "Cropped to {}x{}",
+                    self.universe.width(),
+                    self.universe.height()
+                );
+                self.changed();
+                true
+            }
+            Msg::SetCellSize(cell_size) => {
+                self.cell_size = cell_size.clamp(1, MAX_CELL_SIZE);
+                self.changed();
+                true
+            }
+            Msg::SelectSlot(slot) => {
+                self.slot = slot;
+                true
+            }
+            Msg::Save => {
+                self.save_to(self.slot);
+                log!("Saved to {}", SLOTS[self.slot]);
+                true
+            }
+            Msg::Load => {
+                if self.load_from(self.slot) {
+                    log!("Loaded {}", SLOTS[self.slot]);
+                }
+                true
+            }
+            Msg::Share => {
+                let fragment =
+                    Session::capture(&self.universe, self.cell_size, self.n_steps).to_fragment();
+                if let Some(location) = web_sys::window().map(|window| window.location()) {
+                    let _ = location.set_hash(&fragment);
+                    self.share_link = location.href().unwrap_or_default();
+                }
+                true
+            }
+            Msg::ExportPng => {
+                let options = RenderOptions {
+                    cell_size: self.cell_size,
+                    color_by_age: self.color_by_age,
+                    ..RenderOptions::default()
+                };
+                let png = render::render_universe(&self.universe, &options).to_png();
+                download(
+                    &format!("generation-{}.png", self.universe.generation()),
+                    "image/png",
+                    &png,
+                );
+                false
+            }
+            Msg::ExportSvg => {
+                let options = SvgOptions {
+                    render: RenderOptions {
+                        cell_size: self.cell_size,
+                        ..RenderOptions::default()
+                    },
+                    ..SvgOptions::default()
+                };
+                let (width, height) = (self.universe.width(), self.universe.height());
+                let svg = svg::to_svg(&self.universe, Rect::new(0, 0, width, height), &options);
+                download(
+                    &format!("generation-{}.svg", self.universe.generation()),
+                    "image/svg+xml",
+                    svg.as_bytes(),
+                );
+                false
+            }
+            Msg::ToggleHistory => {
+                let tracking = !self.universe.is_tracking_history();
+                self.universe.set_tracking_history(tracking);
+                true
+            }
+            Msg::ExportHistory => {
+                if let Some(rle) = self.universe.history_rle() {
+                    download(
+                        &format!("generation-{}-history.rle", self.universe.generation()),
+                        "text/plain",
+                        rle.as_bytes(),
+                    );
+                }
+                false
+            }
+            Msg::ToggleHeatmap => {
+                let window = match self.universe.activity() {
+                    Some(_) => None,
+                    None => Some(self.heatmap_window),
+                };
+                self.universe.set_tracking_activity(window);
+                true
+            }
+            Msg::SetHeatmapWindow(window) => {
+                self.heatmap_window = window.max(1);
+                if self.universe.activity().is_some() {
+                    self.universe
+                        .set_tracking_activity(Some(self.heatmap_window));
+                }
+                true
+            }
+            Msg::ToggleAgeColors => {
+                self.color_by_age = !self.color_by_age;
+                if !self.color_by_age {
+                    self.universe.set_tracking_ages(None);
+                }
+                true
+            }
+            Msg::StepBack => {
+                self.seek(None);
+                self.changed();
+                true
+            }
+            Msg::Seek(generation) => {
+                self.seek(Some(generation));
+                self.changed();
+                true
+            }
+            Msg::ToggleChanges => {
+                self.show_changes = !self.show_changes;
+                true
+            }
+            Msg::ExportHeatmap => {
+                if let Some(heatmap) = self.universe.activity() {
+                    download(
+                        &format!("generation-{}-heatmap.csv", self.universe.generation()),
+                        "text/csv",
+                        heatmap.to_csv().as_bytes(),
+                    );
+                }
+                false
+            }
+            Msg::ToggleAutosave => {
+                self.autosave = !self.autosave;
+                if let Some(storage) = storage() {
+                    let _ = storage.set_item(AUTOSAVE_ENABLED_KEY, &self.autosave.to_string());
+                }
+                self.changed();
                 true
             }
         }
@@ -292,6 +729,21 @@ impl Component for UniverseModel {
 
     fn view(&self) -> Html {
         let n_steps = self.n_steps;
+        let density = self.density;
+        let cell_size = self.cell_size;
+        let (width, height) = (self.universe.width(), self.universe.height());
+        let parse_size = |value: yew::events::ChangeData, current: usize| {
+            if let yew::events::ChangeData::Value(str_n) = value {
+                str_n.parse::<usize>().unwrap_or(current)
+            } else {
+                current
+            }
+        };
+        let heatmap = self.universe.activity().is_some();
+        let heatmap_window = self.heatmap_window;
+        let generation = self.universe.generation();
+        let (first, latest) = self.timeline.range().unwrap_or((generation, generation));
+        let latest = latest.max(generation);
         html! {
             <section class="game-area">
                 <div> <fps::FpsModel fps_html={self.fps_html.clone()} /></div>
@@ -309,11 +761,51 @@ impl Component for UniverseModel {
                 <div class="game-buttons">
                     <button class="game-button" onclick=self.link.callback(|_| Msg::TickToggle)> {if self.active {"⏸"} else {"▶"}}</button>
                     <button class="game-button" onclick=self.link.callback(|_| Msg::Random)>{ "Randomize" }</button>
+                    <button class="game-button" onclick=self.link.callback(|_| Msg::StepBack)>{ "Step Back" }</button>
                     <button class="game-button" onclick=self.link.callback(|_| Msg::Step)>{ "Step" }</button>
                     <button class="game-button" onclick=self.link.callback(|_| Msg::Reset)>{ "Clear" }</button>
+                    <div>
+                        <label> { format!("Generation: {}", generation) } </label>
+                        <input type="range" id="timeline" min=first.to_string() max=latest.to_string() value=generation.to_string() onchange=self.link.callback(move |value| {
+                                Msg::Seek(parse_size(value, generation))
+                        }) />
+                        <label> { format!("{} of {} checkpoints, every {} generations", self.timeline.checkpoints().len(), self.timeline.limit(), self.timeline.interval()) } </label>
+                    </div>
+                    <div>
+                        <label> { "Seed: " } </label>
+                        <input type="text" id="soup-seed" value=self.seed.clone() onchange=self.link.callback(|value| {
+                                let mut seed = String::new();
+                                if let yew::events::ChangeData::Value(s) = value {
+                                    seed = s;
+                                }
+                                Msg::SetSeed(seed)
+                        }) />
+                        <label> { format!("Density: {}%", (density * 100.0).round()) } </label>
+                        <input type="range" id="soup-density" min="0" max="100" value=((density * 100.0).round() as usize).to_string() onchange=self.link.callback(move |value| {
+                                let mut d = density;
+                                if let yew::events::ChangeData::Value(str_d) = value {
+                                    if let Ok(percent) = str_d.parse::<f64>() {
+                                        d = percent / 100.0;
+                                    }
+                                }
+                                Msg::SetDensity(d)
+                        }) />
+                        <select id="soup-symmetry" onchange=self.link.callback(|value| {
+                                let mut symmetry = Symmetry::default();
+                                if let yew::events::ChangeData::Select(select) = value {
+                                    symmetry = select.value().parse().unwrap_or_default();
+                                }
+                                Msg::SetSymmetry(symmetry)
+                        })>
+                            { for Symmetry::ALL.iter().map(|s| html! {
+                                <option value=s.name() selected=*s == self.symmetry>{ s.name() }</option>
+                            }) }
+                        </select>
+                        <button class="game-button" onclick=self.link.callback(|_| Msg::Soup)>{ "Soup from Seed" }</button>
+                    </div>
                     <div>
                         <label> { format!("Ticks per Frame: {}", n_steps) } </label>
-                        <input type="range" id="ticks-per-frame" min="1" max="20" value="1" onchange=self.link.callback(move |value| {
+                        <input type="range" id="ticks-per-frame" min="1" max="20" value=n_steps.to_string() onchange=self.link.callback(move |value| {
                                 let mut n = n_steps;
                                 if let yew::events::ChangeData::Value(str_n) = value {
                                     let result = str_n.parse::<usize>();
@@ -324,6 +816,119 @@ impl Component for UniverseModel {
                                 Msg::StepsPerTick(n)
                         }) />
                     </div>
+                    <div>
+                        <label> { format!("Zoom: {}px", cell_size) } </label>
+                        <input type="range" id="cell-size" min="1" max=MAX_CELL_SIZE.to_string() value=cell_size.to_string() onchange=self.link.callback(move |value| {
+                                let mut size = cell_size;
+                                if let yew::events::ChangeData::Value(str_n) = value {
+                                    size = str_n.parse::<usize>().unwrap_or(cell_size);
+                                }
+                                Msg::SetCellSize(size)
+                        }) />
+                    </div>
+                    <div>
+                        <select id="save-slot" onchange=self.link.callback(|value| {
+                                let mut slot = 1;
+                                if let yew::events::ChangeData::Select(select) = value {
+                                    slot = select.value().parse::<usize>().unwrap_or(slot);
+                                }
+                                Msg::SelectSlot(slot)
+                        })>
+                            { for self.slot_descriptions.iter().enumerate().map(|(slot, description)| html! {
+                                <option value=slot.to_string() selected=slot == self.slot>{ description }</option>
+                            }) }
+                        </select>
+                        <button class="game-button" onclick=self.link.callback(|_| Msg::Save)>{ "Save" }</button>
+                        <button class="game-button" onclick=self.link.callback(|_| Msg::Load)>{ "Load" }</button>
+                        <label>
+                            <input type="checkbox" id="autosave" checked=self.autosave onchange=self.link.callback(|_| Msg::ToggleAutosave) />
+                            { "Autosave" }
+                        </label>
+                    </div>
+                    <div>
+                        <button class="game-button" onclick=self.link.callback(|_| Msg::Share)>{ "Share Link" }</button>
+                        <input type="text" id="share-link" readonly=true value=self.share_link.clone() />
+                        <button class="game-button" onclick=self.link.callback(|_| Msg::ExportPng)>{ "Download PNG" }</button>
+                        <button class="game-button" onclick=self.link.callback(|_| Msg::ExportSvg)>{ "Download SVG" }</button>
+                    </div>
+                    <div>
+                        <label>
+                            <input type="checkbox" id="history" checked=self.universe.is_tracking_history() onchange=self.link.callback(|_| Msg::ToggleHistory) />
+                            { "Track History" }
+                        </label>
+                        <button class="game-button" disabled=!self.universe.is_tracking_history() onclick=self.link.callback(|_| Msg::ExportHistory)>{ "Download History RLE" }</button>
+                        <label>
+                            <input type="checkbox" id="heatmap" checked=heatmap onchange=self.link.callback(|_| Msg::ToggleHeatmap) />
+                            { "Heatmap over " }
+                        </label>
+                        <input type="number" id="heatmap-window" min="1" value=heatmap_window.to_string() onchange=self.link.callback(move |value| {
+                                Msg::SetHeatmapWindow(parse_size(value, heatmap_window))
+                        }) />
+                        <label> { " generations" } </label>
+                        <button class="game-button" disabled=!heatmap onclick=self.link.callback(|_| Msg::ExportHeatmap)>{ "Download Heatmap CSV" }</button>
+                        <label>
+                            <input type="checkbox" id="show-changes" checked=self.show_changes onchange=self.link.callback(|_| Msg::ToggleChanges) />
+                            { "Highlight Changes" }
+                        </label>
+                        <label>
+                            <input type="checkbox" id="color-by-age" checked=self.color_by_age onchange=self.link.callback(|_| Msg::ToggleAgeColors) />
+                            { "Color by Age" }
+                        </label>
+                    </div>
+                    <div>
+                        <label> { "Width: " } </label>
+                        <input type="number" id="board-width" min="1" max=MAX_SIZE.to_string() value=width.to_string() onchange=self.link.callback(move |value| {
+                                Msg::Resize(parse_size(value, width), height)
+                        }) />
+                        <label> { "Height: " } </label>
+                        <input type="number" id="board-height" min="1" max=MAX_SIZE.to_string() value=height.to_string() onchange=self.link.callback(move |value| {
+                                Msg::Resize(width, parse_size(value, height))
+                        }) />
+                        <label> { "Keep: " } </label>
+                        <select id="resize-anchor" onchange=self.link.callback(|value| {
+                                let mut anchor = Anchor::default();
+                                if let yew::events::ChangeData::Select(select) = value {
+                                    if let Some((_, a)) = ANCHORS.iter().find(|(name, _)| *name == select.value()) {
+                                        anchor = *a;
+                                    }
+                                }
+                                Msg::SetAnchor(anchor)
+                        })>
+                            { for ANCHORS.iter().map(|(name, a)| html! {
+                                <option value=*name selected=*a == self.anchor>{ *name }</option>
+                            }) }
+                        </select>
+                        <button class="game-button" onclick=self.link.callback(|_| Msg::Crop)>{ "Crop to Pattern" }</button>
+                    </div>
+                    <div>
+                        <label> { "Place: " } </label>
+                        <select id="brush" onchange=self.link.callback(|value| {
+                                let mut brush = None;
+                                if let yew::events::ChangeData::Select(select) = value {
+                                    brush = select.value().parse::<usize>().ok();
+                                }
+                                Msg::SetBrush(brush)
+                        })>
+                            <option value="" selected=self.brush.is_none()>{ "Single Cell" }</option>
+                            { for PATTERNS.iter().enumerate().map(|(index, entry)| html! {
+                                <option value=index.to_string() selected=self.brush == Some(index)>{ entry.name }</option>
+                            }) }
+                        </select>
+                        <select id="brush-transform" onchange=self.link.callback(|value| {
+                                let mut transform = Transform::default();
+                                if let yew::events::ChangeData::Select(select) = value {
+                                    if let Some((_, t)) = TRANSFORMS.iter().find(|(name, _)| *name == select.value()) {
+                                        transform = *t;
+                                    }
+                                }
+                                Msg::SetTransform(transform)
+                        })>
+                            { for TRANSFORMS.iter().map(|(name, t)| html! {
+                                <option value=*name selected=*t == self.transform>{ *name }</option>
+                            }) }
+                        </select>
+                        <pre>{ self.brush_description() }</pre>
+                    </div>
                 </div>
 
 
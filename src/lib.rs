@@ -9,9 +9,17 @@
 #[macro_use]
 mod utils;
 
+#[cfg(not(feature = "no-wasm"))]
 mod app;
+#[cfg(not(feature = "no-wasm"))]
 mod fps;
+pub mod formats;
+#[cfg(not(feature = "no-wasm"))]
 mod game;
+pub mod library;
+pub mod render;
+pub mod search;
+pub mod session;
 pub mod universe;
 
 #[cfg(not(feature = "no-wasm"))]
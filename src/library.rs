@@ -0,0 +1,156 @@
+// built in patterns, stored as RLE
+
+use crate::formats::rle;
+use crate::universe::pattern::Pattern;
+
+/// A named pattern along with what it does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LibraryPattern {
+    pub name: &'static str,
+    pub rle: &'static str,
+    /// generations until it repeats, `None` for patterns that never do (methuselahs)
+    pub period: Option<usize>,
+    /// how far it moves `(x, y)` each period, `(0, 0)` for patterns that stay put
+    pub displacement: (isize, isize),
+    /// size of the bounding box in its starting phase
+    pub width: usize,
+    pub height: usize,
+}
+
+impl LibraryPattern {
+    pub fn pattern(&self) -> Pattern {
+        rle::read(self.rle).expect("library patterns to be valid RLE")
+    }
+
+    pub fn is_spaceship(&self) -> bool {
+        self.displacement != (0, 0)
+    }
+
+    /// speed in the usual `c/4`, `2c/5` notation, `None` for patterns that don't move
+    pub fn speed(&self) -> Option<String> {
+        let period = self.period?;
+        let cells = self
+            .displacement
+            .0
+            .unsigned_abs()
+            .max(self.displacement.1.unsigned_abs());
+        if cells == 0 {
+            return None;
+        }
+
+        let divisor = gcd(cells, period);
+        let (cells, period) = (cells / divisor, period / divisor);
+        let c = if cells == 1 {
+            "c".to_string()
+        } else {
+            format!("{}c", cells)
+        };
+        Some(if period == 1 {
+            c
+        } else {
+            format!("{}/{}", c, period)
+        })
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+pub const PATTERNS: &[LibraryPattern] = &[
+    LibraryPattern {
+        name: "Glider",
+        rle: "x = 3, y = 3\nbo$2bo$3o!",
+        period: Some(4),
+        displacement: (1, 1),
+        width: 3,
+        height: 3,
+    },
+    LibraryPattern {
+        name: "LWSS",
+        rle: "x = 5, y = 4\nbo2bo$o4b$o3bo$4o!",
+        period: Some(4),
+        displacement: (-2, 0),
+        width: 5,
+        height: 4,
+    },
+    LibraryPattern {
+        name: "MWSS",
+        rle: "x = 6, y = 5\n3bo2b$bo3bo$o5b$o4bo$5o!",
+        period: Some(4),
+        displacement: (-2, 0),
+        width: 6,
+        height: 5,
+    },
+    LibraryPattern {
+        name: "HWSS",
+        rle: "x = 7, y = 5\n3b2o2b$bo4bo$o6b$o5bo$6o!",
+        period: Some(4),
+        displacement: (-2, 0),
+        width: 7,
+        height: 5,
+    },
+    LibraryPattern {
+        name: "Gosper glider gun",
+        rle: "x = 36, y = 9\n\
+              24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$\
+              2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!",
+        period: Some(30),
+        displacement: (0, 0),
+        width: 36,
+        height: 9,
+    },
+    LibraryPattern {
+        name: "R-pentomino",
+        rle: "x = 3, y = 3\nb2o$2ob$bo!",
+        period: None,
+        displacement: (0, 0),
+        width: 3,
+        height: 3,
+    },
+    LibraryPattern {
+        name: "Acorn",
+        rle: "x = 7, y = 3\nbo5b$3bo3b$2o2b3o!",
+        period: None,
+        displacement: (0, 0),
+        width: 7,
+        height: 3,
+    },
+    LibraryPattern {
+        name: "Diehard",
+        rle: "x = 8, y = 3\n6bob$2o6b$bo3b3o!",
+        period: None,
+        displacement: (0, 0),
+        width: 8,
+        height: 3,
+    },
+    LibraryPattern {
+        name: "Pulsar",
+        rle: "x = 13, y = 13\n\
+              2b3o3b3o2b2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2b2$\
+              2b3o3b3o2b$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+        period: Some(3),
+        displacement: (0, 0),
+        width: 13,
+        height: 13,
+    },
+    LibraryPattern {
+        name: "Pentadecathlon",
+        rle: "x = 10, y = 3\n2bo4bo2b$2ob4ob2o$2bo4bo!",
+        period: Some(15),
+        displacement: (0, 0),
+        width: 10,
+        height: 3,
+    },
+];
+
+/// looks a pattern up by name, ignoring case
+pub fn find(name: &str) -> Option<&'static LibraryPattern> {
+    PATTERNS
+        .iter()
+        .find(|entry| entry.name.eq_ignore_ascii_case(name))
+}
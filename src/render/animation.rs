@@ -0,0 +1,170 @@
+// animated gifs of a pattern evolving
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use super::{render, Image, RenderOptions};
+use crate::universe::rect::Rect;
+use crate::universe::Universe;
+
+/// Which part of the board goes in each frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crop {
+    /// the same cells in every frame
+    Region(Rect),
+    /// a window the size of the largest bounding box over the whole animation, plus
+    /// `margin` cells on each side, centered on each frame's bounding box so a
+    /// spaceship stays in the middle
+    Follow { margin: usize },
+}
+
+/// How an evolution is turned into an animation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnimationOptions {
+    /// the number of frames, the first one being the board as it is
+    pub frames: usize,
+    /// generations between frames, jumped over with `Universe::step_by` so large
+    /// steps cost little more than small ones once the pattern settles down
+    pub step: usize,
+    /// how long each frame is shown, in milliseconds, gifs only keep hundredths of a second
+    pub delay: usize,
+    pub crop: Crop,
+    pub render: RenderOptions,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        AnimationOptions {
+            frames: 30,
+            step: 1,
+            delay: 100,
+            crop: Crop::Follow { margin: 2 },
+            render: RenderOptions::default(),
+        }
+    }
+}
+
+/// The frames of `universe` evolving, leaving `universe` itself alone.
+///
+/// All the frames are the same size, if the pattern dies out while following its
+/// bounding box the last window it had is kept.
+pub fn frames(universe: &Universe, options: &AnimationOptions) -> Vec<Image> {
+    let regions = match options.crop {
+        Crop::Region(region) => vec![region; options.frames],
+        Crop::Follow { margin } => follow(universe, options, margin),
+    };
+
+    let mut universe = universe.clone();
+    let mut images = Vec::with_capacity(options.frames);
+    for (frame, region) in regions.iter().enumerate() {
+        if frame > 0 {
+            universe.step_by(options.step);
+        }
+        images.push(render(&universe, *region, &options.render));
+    }
+    images
+}
+
+/// the window for each frame when following the bounding box
+fn follow(universe: &Universe, options: &AnimationOptions, margin: usize) -> Vec<Rect> {
+    let mut universe = universe.clone();
+    let mut boxes = Vec::with_capacity(options.frames);
+    for frame in 0..options.frames {
+        if frame > 0 {
+            universe.step_by(options.step);
+        }
+        boxes.push(universe.bounding_box());
+    }
+
+    let width = boxes.iter().flatten().map(Rect::width).max().unwrap_or(1) + margin * 2;
+    let height = boxes.iter().flatten().map(Rect::height).max().unwrap_or(1) + margin * 2;
+
+    let mut last = Rect::new(0, 0, width, height);
+    boxes
+        .iter()
+        .map(|bounds| {
+            if let Some(bounds) = bounds {
+                // centered on the bounding box, but not off the top or left of the board
+                let x = (bounds.x() + bounds.width() / 2).saturating_sub(width / 2);
+                let y = (bounds.y() + bounds.height() / 2).saturating_sub(height / 2);
+                last = Rect::new(x, y, width, height);
+            }
+            last
+        })
+        .collect()
+}
+
+/// Encodes `frames` as a looping gif.
+///
+/// The frames all have to be the same size, no more than 65535 pixels a side,
+/// and have no more than 256 colors between them. Anything fully transparent is
+/// left transparent in the gif.
+pub fn to_gif(frames: &[Image], delay: usize) -> Result<Vec<u8>, &'static str> {
+    let first = frames.first().ok_or("there are no frames")?;
+    if frames
+        .iter()
+        .any(|frame| (frame.width(), frame.height()) != (first.width(), first.height()))
+    {
+        return Err("the frames are different sizes");
+    }
+    let too_large = || "too large for a gif";
+    let width = u16::try_from(first.width()).map_err(|_| too_large())?;
+    let height = u16::try_from(first.height()).map_err(|_| too_large())?;
+
+    // one palette for every frame, in the order the colors are first seen
+    let mut palette = Vec::new();
+    let mut indices = HashMap::new();
+    let mut transparent = None;
+    let mut indexed = Vec::with_capacity(frames.len());
+    for frame in frames {
+        let mut buffer = Vec::with_capacity(frame.width() * frame.height());
+        for pixel in frame.pixels().chunks_exact(4) {
+            let key = if pixel[3] == 0 {
+                [0; 4]
+            } else {
+                [pixel[0], pixel[1], pixel[2], 0xFF]
+            };
+            let next = indices.len();
+            let index = *indices.entry(key).or_insert(next);
+            if index == next {
+                if next == 256 {
+                    return Err("more than 256 colors");
+                }
+                palette.extend_from_slice(&key[..3]);
+                if key[3] == 0 {
+                    transparent = Some(index as u8);
+                }
+            }
+            buffer.push(index as u8);
+        }
+        indexed.push(buffer);
+    }
+
+    let mut bytes = Vec::new();
+    {
+        // writing to a Vec can't fail
+        let mut encoder =
+            gif::Encoder::new(&mut bytes, width, height, &palette).expect("gif header");
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .expect("gif repeat");
+        for buffer in &indexed {
+            let frame = gif::Frame {
+                width,
+                height,
+                delay: u16::try_from(delay.div_ceil(10)).unwrap_or(u16::MAX),
+                transparent,
+                buffer: Cow::Borrowed(buffer),
+                ..gif::Frame::default()
+            };
+            encoder.write_frame(&frame).expect("gif frame");
+        }
+    }
+    Ok(bytes)
+}
+
+/// Steps a copy of `universe` and encodes it as a looping gif
+pub fn animate(universe: &Universe, options: &AnimationOptions) -> Result<Vec<u8>, &'static str> {
+    to_gif(&frames(universe, options), options.delay)
+}
@@ -0,0 +1,210 @@
+// keeping a drawing of the board up to date by repainting only what changed
+
+use std::collections::HashMap;
+
+use super::{background, paint_cells, Color, Image, RenderOptions};
+use crate::universe::node::NodeId;
+use crate::universe::rect::{self, Rect};
+use crate::universe::Universe;
+
+/// past this many separate changed areas they're repainted as one rectangle
+/// around all of them, which is quicker than copying out lots of small pieces
+pub const MAX_DIRTY_RECTS: usize = 64;
+
+/// Colors painted over single cells on top of the board, blended by their alpha,
+/// like the heatmap or highlighted births and deaths.
+pub type Overlay = HashMap<(usize, usize), Color>;
+
+/// A drawing of the whole board that follows a universe from frame to frame.
+///
+/// The dead cells and grid are drawn once into a background that is kept for
+/// full redraws. After that each `update` compares the roots it last drew with the
+/// universe's current ones, using `Universe::diff`, and only repaints the cells
+/// whose part of the quadtree changed. Coloring by age repaints every live cell
+/// each generation, since they all get older.
+///
+/// An overlay is kept next to the board and blended into the image that is shown,
+/// so only the cells whose overlay color changed are repainted along with the
+/// ones the board changed under.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    options: RenderOptions,
+    width: usize,
+    height: usize,
+    background: Image,
+    /// the board without the overlay
+    board: Image,
+    /// the board with the overlay blended in
+    image: Image,
+    overlay: Overlay,
+    /// the root and envelope last drawn, `None` until the first full draw
+    drawn: Option<(NodeId, Option<NodeId>)>,
+    drawn_generation: usize,
+    /// whether the live cells were last drawn colored by age
+    drawn_by_age: bool,
+}
+
+impl Frame {
+    /// a frame for a `width` x `height` board, drawn in full on the first update
+    pub fn new(width: usize, height: usize, options: RenderOptions) -> Self {
+        let background = background(width, height, &options);
+        Frame {
+            options,
+            width,
+            height,
+            board: background.clone(),
+            image: background.clone(),
+            background,
+            overlay: Overlay::new(),
+            drawn: None,
+            drawn_generation: 0,
+            drawn_by_age: false,
+        }
+    }
+
+    pub fn options(&self) -> &RenderOptions {
+        &self.options
+    }
+
+    /// the board as last drawn, overlay and all
+    pub fn image(&self) -> &Image {
+        &self.image
+    }
+
+    /// forgets what was drawn so the next update repaints everything. needed when
+    /// the universe is swapped for another one, since node ids only mean anything
+    /// inside the universe they came from
+    pub fn invalidate(&mut self) {
+        self.drawn = None;
+    }
+
+    /// brings the drawing up to date with `universe`, without an overlay. returns
+    /// the rectangles of pixels that were repainted, nothing changed if it's empty
+    pub fn update(&mut self, universe: &Universe) -> Vec<Rect> {
+        self.update_with_overlay(universe, Overlay::new())
+    }
+
+    /// brings the drawing up to date with `universe` and `overlay`, see `update`
+    pub fn update_with_overlay(&mut self, universe: &Universe, overlay: Overlay) -> Vec<Rect> {
+        if (universe.width(), universe.height()) != (self.width, self.height) {
+            *self = Frame::new(universe.width(), universe.height(), self.options);
+        }
+
+        let mut cells = match self.update_board(universe) {
+            Some(cells) => cells,
+            None => {
+                self.image = self.board.clone();
+                self.overlay = overlay;
+                self.paint_overlay(self.overlay.keys().cloned().collect::<Vec<_>>());
+                return vec![Rect::new(0, 0, self.image.width(), self.image.height())];
+            }
+        };
+
+        cells.extend(
+            overlay
+                .iter()
+                .filter(|&(cell, color)| self.overlay.get(cell) != Some(color))
+                .map(|(&cell, _)| cell),
+        );
+        cells.extend(
+            self.overlay
+                .keys()
+                .filter(|cell| !overlay.contains_key(cell))
+                .cloned(),
+        );
+        cells.sort_unstable_by_key(|&(x, y)| (y, x));
+        cells.dedup();
+        self.overlay = overlay;
+        for &cell in &cells {
+            let pixels = self.pixels(Rect::new(cell.0, cell.1, 1, 1));
+            self.image.copy_rect(&self.board, pixels);
+        }
+        self.paint_overlay(cells.iter().cloned());
+
+        let rects = rect::cover(&mut cells);
+        if rects.len() > MAX_DIRTY_RECTS {
+            let left = rects.iter().map(Rect::x).min().unwrap_or(0);
+            let top = rects.iter().map(Rect::y).min().unwrap_or(0);
+            let right = rects.iter().map(Rect::right).max().unwrap_or(0);
+            let bottom = rects.iter().map(Rect::bottom).max().unwrap_or(0);
+            vec![self.pixels(Rect::new(left, top, right - left, bottom - top))]
+        } else {
+            rects.into_iter().map(|cells| self.pixels(cells)).collect()
+        }
+    }
+
+    /// repaints the board where it changed, returning the cells repainted, or `None`
+    /// if it was redrawn in full
+    fn update_board(&mut self, universe: &Universe) -> Option<Vec<(usize, usize)>> {
+        let root = universe.root();
+        let envelope = universe.envelope_root();
+        let (old_root, old_envelope) = match self.drawn {
+            // turning history on or off changes the color of every cell it ever covered
+            Some((old_root, old_envelope)) if old_envelope.is_some() == envelope.is_some() => {
+                (old_root, old_envelope)
+            }
+            _ => return self.redraw(universe),
+        };
+        // every live cell gets a generation older each step, so they all change color
+        let by_age = self.options.color_by_age && universe.ages().is_some();
+        if by_age || self.drawn_by_age {
+            let same = self.drawn_generation == universe.generation() && old_root == root;
+            if by_age && self.drawn_by_age && same {
+                return Some(vec![]);
+            }
+            return self.redraw(universe);
+        }
+        self.drawn = Some((root, envelope));
+        self.drawn_generation = universe.generation();
+
+        let mut cells: Vec<_> = universe.diff(old_root, root).changed().collect();
+        if let (Some(old_envelope), Some(envelope)) = (old_envelope, envelope) {
+            cells.extend(universe.diff(old_envelope, envelope).changed());
+        }
+
+        let options = self.options;
+        for &(x, y) in &cells {
+            let color = if universe.get_cell(x, y) == Ok(true) {
+                options.alive
+            } else if universe.in_envelope(x, y) {
+                options.envelope
+            } else {
+                options.dead
+            };
+            let (px, py) = options.cell_origin(x, y);
+            self.board
+                .fill_rect(px, py, options.cell_size, options.cell_size, color);
+        }
+        Some(cells)
+    }
+
+    /// paints the whole board over a fresh copy of the background
+    fn redraw(&mut self, universe: &Universe) -> Option<Vec<(usize, usize)>> {
+        self.board = self.background.clone();
+        let board = Rect::new(0, 0, self.width, self.height);
+        paint_cells(&mut self.board, universe, board, &self.options);
+        self.drawn = Some((universe.root(), universe.envelope_root()));
+        self.drawn_generation = universe.generation();
+        self.drawn_by_age = self.options.color_by_age && universe.ages().is_some();
+        None
+    }
+
+    /// blends the overlay into the shown image for each of `cells` that has a color
+    fn paint_overlay(&mut self, cells: impl IntoIterator<Item = (usize, usize)>) {
+        let size = self.options.cell_size;
+        for (x, y) in cells {
+            if let Some(&color) = self.overlay.get(&(x, y)) {
+                let (px, py) = self.options.cell_origin(x, y);
+                self.image.blend_rect(px, py, size, size, color);
+            }
+        }
+    }
+
+    /// the pixels covering a rectangle of cells, not counting the grid around it
+    fn pixels(&self, cells: Rect) -> Rect {
+        let (x, y) = self.options.cell_origin(cells.x(), cells.y());
+        let (right, bottom) = self.options.cell_origin(cells.right(), cells.bottom());
+        let grid = self.options.grid as usize;
+        Rect::new(x, y, right - x - grid, bottom - y - grid)
+    }
+}
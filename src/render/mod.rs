@@ -0,0 +1,342 @@
+// drawing the board to an image outside of the canvas, for exporting
+
+pub mod animation;
+pub mod frame;
+pub mod svg;
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::universe::rect::Rect;
+use crate::universe::Universe;
+
+pub const GRID_COLOR: Color = Color::rgb(0xCC, 0xCC, 0xCC);
+pub const DEAD_COLOR: Color = Color::rgb(0xFF, 0xFF, 0xFF);
+pub const ALIVE_COLOR: Color = Color::rgb(0x00, 0x00, 0x00);
+/// cells that have been alive, but aren't now, when history is tracked
+pub const ENVELOPE_COLOR: Color = Color::rgb(0x9E, 0xC9, 0xF5);
+/// cells that were just born or just died, when changes are highlighted
+pub const BIRTH_COLOR: Color = Color::rgba(0x2E, 0xCC, 0x40, 0xA0);
+pub const DEATH_COLOR: Color = Color::rgba(0xFF, 0x41, 0x36, 0xA0);
+
+/// the color of a cell on a heatmap overlay that changed `count` times, the busiest
+/// cell having changed `max` times. see through yellow for the quietest cells up to
+/// a stronger red, and nothing at all for cells that never changed
+pub fn heat_color(count: u32, max: u32) -> Color {
+    if count == 0 || max == 0 {
+        return Color::rgba(0, 0, 0, 0);
+    }
+    let t = count.min(max) as f64 / max as f64;
+    let mix = |from: f64, to: f64| (from + (to - from) * t).round() as u8;
+    Color::rgba(mix(255.0, 220.0), mix(220.0, 0.0), 0, mix(80.0, 208.0))
+}
+
+/// cells this old, or older, are all drawn the same when coloring by age
+pub const OLD_AGE: usize = 100;
+
+/// the color of a live cell `age` generations after it was born when coloring by
+/// age, from bright orange for newborns down to a dim slate for anything that has
+/// been around for `OLD_AGE` generations. the scale is logarithmic so the first few
+/// generations stand apart
+pub fn age_color(age: usize) -> Color {
+    let t = ((age.min(OLD_AGE) + 1) as f64).ln() / ((OLD_AGE + 1) as f64).ln();
+    let mix = |from: f64, to: f64| (from + (to - from) * t).round() as u8;
+    Color::rgb(mix(255.0, 90.0), mix(106.0, 100.0), mix(0.0, 120.0))
+}
+
+/// An RGBA color
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b, a: 0xFF }
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Color {
+        Color { r, g, b, a }
+    }
+
+    pub fn to_rgba(self) -> [u8; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+
+    /// this color painted over `below`, blended by alpha the way a canvas does
+    pub fn over(self, below: Color) -> Color {
+        let (top_alpha, below_alpha) = (self.a as f64 / 255.0, below.a as f64 / 255.0);
+        let alpha = top_alpha + below_alpha * (1.0 - top_alpha);
+        if alpha == 0.0 {
+            return Color::rgba(0, 0, 0, 0);
+        }
+        let mix = |top: u8, bottom: u8| {
+            let mixed = top as f64 * top_alpha + bottom as f64 * below_alpha * (1.0 - top_alpha);
+            (mixed / alpha).round() as u8
+        };
+        Color::rgba(
+            mix(self.r, below.r),
+            mix(self.g, below.g),
+            mix(self.b, below.b),
+            (alpha * 255.0).round() as u8,
+        )
+    }
+
+    /// the color as `#rrggbb`, or `#rrggbbaa` when it isn't opaque, for css and svg
+    pub fn css(self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02X}{:02X}{:02X}", self.r, self.g, self.b)?;
+        if self.a != 0xFF {
+            write!(f, "{:02X}", self.a)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Color {
+    type Err = &'static str;
+
+    /// `#rgb`, `#rrggbb` or `#rrggbbaa`, the `#` being optional
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let hex = text.strip_prefix('#').unwrap_or(text);
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err("colors are hex, like #CCCCCC");
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap();
+        match hex.len() {
+            3 => {
+                let short = |i: usize| u8::from_str_radix(&hex[i..=i], 16).unwrap() * 0x11;
+                Ok(Color::rgb(short(0), short(1), short(2)))
+            }
+            6 => Ok(Color::rgb(channel(0), channel(1), channel(2))),
+            8 => Ok(Color::rgba(channel(0), channel(1), channel(2), channel(3))),
+            _ => Err("colors are hex, like #CCCCCC"),
+        }
+    }
+}
+
+/// How a board is drawn, the defaults matching the canvas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// the side of a cell, in pixels
+    pub cell_size: usize,
+    /// a one pixel line between cells and around the edge
+    pub grid: bool,
+    pub alive: Color,
+    pub dead: Color,
+    pub grid_color: Color,
+    /// drawn under the live cells when the universe is tracking history
+    pub envelope: Color,
+    /// live cells the universe is tracking ages for are drawn with `age_color`
+    /// instead of `alive`
+    pub color_by_age: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            cell_size: 5,
+            grid: true,
+            alive: ALIVE_COLOR,
+            dead: DEAD_COLOR,
+            grid_color: GRID_COLOR,
+            envelope: ENVELOPE_COLOR,
+            color_by_age: false,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// distance in pixels from one cell to the next
+    fn pitch(&self) -> usize {
+        self.cell_size + self.grid as usize
+    }
+
+    /// the size in pixels of `width` by `height` cells
+    pub fn image_size(&self, width: usize, height: usize) -> (usize, usize) {
+        let edge = self.grid as usize;
+        (width * self.pitch() + edge, height * self.pitch() + edge)
+    }
+
+    /// the top left pixel of a cell, counted from the corner of the region
+    pub fn cell_origin(&self, x: usize, y: usize) -> (usize, usize) {
+        let edge = self.grid as usize;
+        (x * self.pitch() + edge, y * self.pitch() + edge)
+    }
+}
+
+/// An RGBA image, row by row with four bytes to a pixel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, fill: Color) -> Image {
+        Image {
+            width,
+            height,
+            pixels: fill.to_rgba().repeat(width * height),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn get_pixel(&self, x: usize, y: usize) -> Color {
+        let i = (y * self.width + x) * 4;
+        let p = &self.pixels[i..i + 4];
+        Color::rgba(p[0], p[1], p[2], p[3])
+    }
+
+    /// the pixels inside `rect`, row by row, for copying part of the image out
+    pub fn region_pixels(&self, rect: Rect) -> Vec<u8> {
+        let mut pixels = Vec::with_capacity(rect.width() * rect.height() * 4);
+        for row in rect.y()..rect.bottom() {
+            let start = (row * self.width + rect.x()) * 4;
+            pixels.extend_from_slice(&self.pixels[start..start + rect.width() * 4]);
+        }
+        pixels
+    }
+
+    /// fills a rectangle of pixels, clipped to the image
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Color) {
+        let right = (x + width).min(self.width);
+        let bottom = (y + height).min(self.height);
+        if x >= right {
+            return;
+        }
+        let rgba = color.to_rgba();
+        for row in y..bottom {
+            let start = (row * self.width + x) * 4;
+            let end = (row * self.width + right) * 4;
+            for pixel in self.pixels[start..end].chunks_exact_mut(4) {
+                pixel.copy_from_slice(&rgba);
+            }
+        }
+    }
+
+    /// paints `color` over a rectangle of pixels, blending by alpha, clipped to the image
+    pub fn blend_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Color) {
+        let right = (x + width).min(self.width);
+        let bottom = (y + height).min(self.height);
+        for row in y..bottom {
+            for column in x..right {
+                let blended = color.over(self.get_pixel(column, row));
+                let i = (row * self.width + column) * 4;
+                self.pixels[i..i + 4].copy_from_slice(&blended.to_rgba());
+            }
+        }
+    }
+
+    /// copies the pixels inside `rect` from an image of the same size
+    pub fn copy_rect(&mut self, from: &Image, rect: Rect) {
+        let right = rect.right().min(self.width).min(from.width);
+        let bottom = rect.bottom().min(self.height).min(from.height);
+        if rect.x() >= right {
+            return;
+        }
+        for row in rect.y()..bottom {
+            let start = (row * self.width + rect.x()) * 4;
+            let end = (row * self.width + right) * 4;
+            let from_start = (row * from.width + rect.x()) * 4;
+            self.pixels[start..end]
+                .copy_from_slice(&from.pixels[from_start..from_start + end - start]);
+        }
+    }
+
+    /// the image encoded as a PNG file
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        // writing to a Vec can't fail, and the size always matches the data
+        let mut writer = encoder.write_header().expect("png header");
+        writer.write_image_data(&self.pixels).expect("png data");
+        writer.finish().expect("png end");
+        bytes
+    }
+}
+
+/// Draws `region` of the board, the same way the canvas does.
+///
+/// Only the live cells are visited, so large and mostly empty regions stay cheap
+/// apart from filling in the background. Anything past the edge of the board is dead.
+pub fn render(universe: &Universe, region: Rect, options: &RenderOptions) -> Image {
+    let mut image = background(region.width(), region.height(), options);
+    paint_cells(&mut image, universe, region, options);
+    image
+}
+
+/// a `width` x `height` cell board with every cell dead, grid and all
+pub fn background(width: usize, height: usize, options: &RenderOptions) -> Image {
+    let (width, height) = options.image_size(width, height);
+    let mut image = Image::new(width, height, options.dead);
+
+    if options.grid {
+        let pitch = options.pitch();
+        for column in (0..width).step_by(pitch) {
+            image.fill_rect(column, 0, 1, height, options.grid_color);
+        }
+        for row in (0..height).step_by(pitch) {
+            image.fill_rect(0, row, width, 1, options.grid_color);
+        }
+    }
+    image
+}
+
+/// paints the envelope and live cells of `region` onto a background of the same size
+pub(crate) fn paint_cells(
+    image: &mut Image,
+    universe: &Universe,
+    region: Rect,
+    options: &RenderOptions,
+) {
+    for (x, y) in universe.envelope_cells_in(region).into_iter().flatten() {
+        let (px, py) = options.cell_origin(x - region.x(), y - region.y());
+        image.fill_rect(
+            px,
+            py,
+            options.cell_size,
+            options.cell_size,
+            options.envelope,
+        );
+    }
+    let ages = universe.ages().filter(|_| options.color_by_age);
+    for (x, y) in universe.live_cells_in(region) {
+        let (px, py) = options.cell_origin(x - region.x(), y - region.y());
+        let color = ages
+            .and_then(|ages| ages.get(x, y))
+            .map_or(options.alive, age_color);
+        image.fill_rect(px, py, options.cell_size, options.cell_size, color);
+    }
+}
+
+/// Draws the whole board
+pub fn render_universe(universe: &Universe, options: &RenderOptions) -> Image {
+    render(
+        universe,
+        Rect::new(0, 0, universe.width(), universe.height()),
+        options,
+    )
+}
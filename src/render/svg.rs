@@ -0,0 +1,191 @@
+// scalable drawings of the board, for print
+
+use std::fmt::Write;
+
+use super::{Color, RenderOptions};
+use crate::universe::rect::{self, Rect};
+use crate::universe::Universe;
+
+/// The default color for highlighted regions, a see through yellow
+pub const HIGHLIGHT_COLOR: Color = Color::rgba(0xFF, 0xD7, 0x00, 0x60);
+
+/// A region of the board to draw attention to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Highlight {
+    /// in board coordinates
+    pub region: Rect,
+    pub color: Color,
+}
+
+impl Highlight {
+    pub fn new(region: Rect) -> Self {
+        Highlight {
+            region,
+            color: HIGHLIGHT_COLOR,
+        }
+    }
+}
+
+/// How a board is drawn as svg, on top of the options shared with the images
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SvgOptions {
+    pub render: RenderOptions,
+    /// numbers the rows and columns along the top and left, every so many cells
+    pub labels: Option<usize>,
+    pub highlights: Vec<Highlight>,
+}
+
+/// `fill="#rrggbb"` or `stroke=...`, with an opacity when the color isn't opaque,
+/// since not everything reads eight digit colors
+fn paint(attribute: &str, color: Color) -> String {
+    let rgb = Color { a: 0xFF, ..color }.css();
+    if color.a == 0xFF {
+        format!("{}=\"{}\"", attribute, rgb)
+    } else {
+        format!(
+            "{a}=\"{}\" {a}-opacity=\"{:.3}\"",
+            rgb,
+            color.a as f64 / 255.0,
+            a = attribute
+        )
+    }
+}
+
+/// Draws `region` of the board as an svg document.
+///
+/// Cells are laid out exactly as in `render`, a pixel there being a unit here, with
+/// the labels, if any, in a margin above and to the left. Only the live cells are
+/// visited, and runs of them become single rectangles.
+pub fn to_svg(universe: &Universe, region: Rect, options: &SvgOptions) -> String {
+    let render = &options.render;
+    let (width, height) = render.image_size(region.width(), region.height());
+    let pitch = render.cell_size + render.grid as usize;
+
+    let font_size = render.cell_size.max(6);
+    let (left, top) = match options.labels {
+        Some(_) => {
+            let digits = (region.x() + region.width())
+                .max(region.y() + region.height())
+                .to_string()
+                .len();
+            // roughly the width of a digit, plus a bit of room
+            ((digits * font_size * 3).div_ceil(5) + 2, font_size + 2)
+        }
+        None => (0, 0),
+    };
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+        w = width + left,
+        h = height + top
+    )
+    .unwrap();
+
+    writeln!(svg, "<g transform=\"translate({} {})\">", left, top).unwrap();
+    if render.dead.a != 0 {
+        writeln!(
+            svg,
+            "<rect width=\"{}\" height=\"{}\" {}/>",
+            width,
+            height,
+            paint("fill", render.dead)
+        )
+        .unwrap();
+    }
+
+    let mut cells: Vec<_> = universe.live_cells_in(region).collect();
+    writeln!(svg, "<g {}>", paint("fill", render.alive)).unwrap();
+    for block in rect::cover(&mut cells) {
+        let (x, y) = render.cell_origin(block.x() - region.x(), block.y() - region.y());
+        writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>",
+            x,
+            y,
+            block.width() * pitch - render.grid as usize,
+            block.height() * pitch - render.grid as usize
+        )
+        .unwrap();
+    }
+    writeln!(svg, "</g>").unwrap();
+
+    for highlight in &options.highlights {
+        // clipped to the region being drawn
+        let x = highlight.region.x().max(region.x());
+        let y = highlight.region.y().max(region.y());
+        let right = highlight.region.right().min(region.right());
+        let bottom = highlight.region.bottom().min(region.bottom());
+        if x >= right || y >= bottom {
+            continue;
+        }
+        let (px, py) = render.cell_origin(x - region.x(), y - region.y());
+        writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" {}/>",
+            px,
+            py,
+            (right - x) * pitch - render.grid as usize,
+            (bottom - y) * pitch - render.grid as usize,
+            paint("fill", highlight.color)
+        )
+        .unwrap();
+    }
+
+    // drawn last, so it runs between live cells the way it does on the canvas
+    if render.grid {
+        let mut path = String::new();
+        for column in 0..=region.width() {
+            write!(path, "M{}.5 0V{}", column * pitch, height).unwrap();
+        }
+        for row in 0..=region.height() {
+            write!(path, "M0 {}.5H{}", row * pitch, width).unwrap();
+        }
+        writeln!(
+            svg,
+            "<path d=\"{}\" {} stroke-width=\"1\" fill=\"none\"/>",
+            path,
+            paint("stroke", render.grid_color)
+        )
+        .unwrap();
+    }
+    writeln!(svg, "</g>").unwrap();
+
+    if let Some(every) = options.labels.filter(|&every| every > 0) {
+        writeln!(
+            svg,
+            "<g font-family=\"monospace\" font-size=\"{}\" {}>",
+            font_size,
+            paint("fill", render.alive)
+        )
+        .unwrap();
+        let first = |start: usize| start.div_ceil(every) * every;
+        for column in (first(region.x())..region.right()).step_by(every) {
+            let (x, _) = render.cell_origin(column - region.x(), 0);
+            writeln!(
+                svg,
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+                left as f64 + x as f64 + render.cell_size as f64 / 2.0,
+                font_size,
+                column
+            )
+            .unwrap();
+        }
+        for row in (first(region.y())..region.bottom()).step_by(every) {
+            let (_, y) = render.cell_origin(0, row - region.y());
+            writeln!(
+                svg,
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"end\" dominant-baseline=\"central\">{}</text>",
+                left - 2,
+                top as f64 + y as f64 + render.cell_size as f64 / 2.0,
+                row
+            )
+            .unwrap();
+        }
+        writeln!(svg, "</g>").unwrap();
+    }
+
+    writeln!(svg, "</svg>").unwrap();
+    svg
+}
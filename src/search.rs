@@ -0,0 +1,296 @@
+// running lots of soups and counting what they settle into, like apgsearch
+
+use std::fmt::Write;
+
+use crate::formats::apgcode::Apgcode;
+use crate::formats::FormatError;
+use crate::universe::census::{self, Census, CensusEntry, ObjectKind};
+use crate::universe::pattern::PasteMode;
+use crate::universe::soup::{self, Symmetry};
+use crate::universe::Universe;
+
+/// soups are this many cells wide and tall, as in apgsearch
+pub const SOUP_SIZE: usize = 16;
+
+/// the board each soup runs on. big enough that gliders can't come back
+/// around and hit the ash before `SETTLE_LIMIT`
+const BOARD_SIZE: usize = 2048;
+
+/// soups that haven't settled after this many generations are set aside
+pub const SETTLE_LIMIT: usize = 6000;
+
+/// how many generations the population has to have repeated for to count as settled
+const SETTLE_WINDOW: usize = 2 * census::DEFAULT_MAX_PERIOD;
+
+/// still lifes at least this big are rare enough to keep the seed of
+const RARE_POPULATION: usize = 16;
+
+const CHECKPOINT_HEADER: &str = "#soup-search";
+
+/// A soup search in progress: the soups run so far and everything they settled into.
+///
+/// soup seeds are the prefix followed by a count, so a search can be picked up
+/// where it left off from a checkpoint (see `checkpoint` and `from_checkpoint`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoupSearch {
+    prefix: String,
+    symmetry: Symmetry,
+    soups: usize,
+    census: Census,
+    /// rare objects, and the seed of the soup they came from
+    finds: Vec<(String, String)>,
+    /// seeds of soups that didn't settle
+    unsettled: Vec<String>,
+}
+
+impl SoupSearch {
+    /// a search that hasn't run any soups yet. the prefix can't be empty or have
+    /// whitespace in it, so it can be written to a checkpoint and read back
+    pub fn new(prefix: &str, symmetry: Symmetry) -> Result<Self, &'static str> {
+        if prefix.is_empty() || prefix.chars().any(char::is_whitespace) {
+            return Err("the seed prefix can't be empty or have whitespace in it");
+        }
+        Ok(SoupSearch {
+            prefix: prefix.to_string(),
+            symmetry,
+            soups: 0,
+            census: Census::default(),
+            finds: vec![],
+            unsettled: vec![],
+        })
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn symmetry(&self) -> Symmetry {
+        self.symmetry
+    }
+
+    /// how many soups have been run
+    pub fn soups(&self) -> usize {
+        self.soups
+    }
+
+    /// every object from every settled soup
+    pub fn census(&self) -> &Census {
+        &self.census
+    }
+
+    /// the apgcode of each rare object found, and the seed of its soup
+    pub fn finds(&self) -> &[(String, String)] {
+        &self.finds
+    }
+
+    pub fn unsettled(&self) -> &[String] {
+        &self.unsettled
+    }
+
+    /// the seed of the next soup
+    pub fn next_seed(&self) -> String {
+        format!("{}{}", self.prefix, self.soups)
+    }
+
+    /// runs the next `count` soups
+    pub fn run(&mut self, count: usize) {
+        for _ in 0..count {
+            self.run_next();
+        }
+    }
+
+    /// runs the next soup, returning its census if it settled
+    pub fn run_next(&mut self) -> Option<Census> {
+        let seed = self.next_seed();
+        self.soups += 1;
+
+        let census = match run_soup(&seed, self.symmetry) {
+            Some(census) => census,
+            None => {
+                self.unsettled.push(seed);
+                return None;
+            }
+        };
+
+        for entry in census.entries() {
+            if is_rare(entry) {
+                self.finds.push((entry.apgcode.clone(), seed.clone()));
+            }
+            self.census.add(entry.clone(), entry.count);
+        }
+        Some(census)
+    }
+
+    /// a summary of the search, most common objects first
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+        writeln!(
+            report,
+            "{} soups of {} with seeds {}0 to {}",
+            self.soups,
+            self.symmetry,
+            self.prefix,
+            self.soups.saturating_sub(1)
+        )
+        .unwrap();
+        writeln!(report, "{} objects", self.census.total()).unwrap();
+        for entry in self.census.by_frequency() {
+            writeln!(report, "{:>10} {}", entry.count, entry.apgcode).unwrap();
+        }
+        if !self.finds.is_empty() {
+            writeln!(report, "rare finds:").unwrap();
+            for (apgcode, seed) in self.finds.iter() {
+                writeln!(report, "  {} in {}", apgcode, seed).unwrap();
+            }
+        }
+        if !self.unsettled.is_empty() {
+            writeln!(report, "didn't settle:").unwrap();
+            for seed in self.unsettled.iter() {
+                writeln!(report, "  {}", seed).unwrap();
+            }
+        }
+        report
+    }
+
+    /// the whole state of the search as text, one fact per line
+    pub fn checkpoint(&self) -> String {
+        let mut text = String::new();
+        writeln!(text, "{}", CHECKPOINT_HEADER).unwrap();
+        writeln!(text, "prefix {}", self.prefix).unwrap();
+        writeln!(text, "symmetry {}", self.symmetry).unwrap();
+        writeln!(text, "soups {}", self.soups).unwrap();
+        for entry in self.census.entries() {
+            writeln!(text, "object {} {}", entry.apgcode, entry.count).unwrap();
+        }
+        for (apgcode, seed) in self.finds.iter() {
+            writeln!(text, "find {} {}", apgcode, seed).unwrap();
+        }
+        for seed in self.unsettled.iter() {
+            writeln!(text, "unsettled {}", seed).unwrap();
+        }
+        text
+    }
+
+    /// picks a search back up from what `checkpoint` wrote
+    pub fn from_checkpoint(text: &str) -> Result<Self, FormatError> {
+        let mut lines = text.lines();
+        match lines.next() {
+            Some(header) if header.trim() == CHECKPOINT_HEADER => {}
+            Some(header) => return Err(FormatError::InvalidHeader(header.to_string())),
+            None => return Err(FormatError::UnexpectedEnd { line: 1 }),
+        }
+
+        let mut search = SoupSearch {
+            prefix: String::new(),
+            symmetry: Symmetry::default(),
+            soups: 0,
+            census: Census::default(),
+            finds: vec![],
+            unsettled: vec![],
+        };
+        for (number, line) in lines.enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = || FormatError::InvalidLine {
+                line: number + 2,
+                text: line.to_string(),
+            };
+            let mut words = line.split_whitespace();
+            let key = words.next().ok_or_else(invalid)?;
+            let values: Vec<_> = words.collect();
+            match (key, values.as_slice()) {
+                ("prefix", [prefix]) => search.prefix = prefix.to_string(),
+                ("symmetry", [symmetry]) => {
+                    search.symmetry = symmetry.parse().map_err(|_| invalid())?
+                }
+                ("soups", [soups]) => search.soups = soups.parse().map_err(|_| invalid())?,
+                ("object", [apgcode, count]) => {
+                    let count = count.parse().map_err(|_| invalid())?;
+                    let entry = entry_for(apgcode).map_err(|_| invalid())?;
+                    search.census.add(entry, count);
+                }
+                ("find", [apgcode, seed]) => {
+                    search.finds.push((apgcode.to_string(), seed.to_string()))
+                }
+                ("unsettled", [seed]) => search.unsettled.push(seed.to_string()),
+                _ => return Err(invalid()),
+            }
+        }
+        if search.prefix.is_empty() {
+            return Err(FormatError::UnexpectedEnd {
+                line: text.lines().count(),
+            });
+        }
+        Ok(search)
+    }
+}
+
+/// runs the soup with `seed` until it settles and takes its census, `None` if it
+/// hasn't settled after `SETTLE_LIMIT` generations
+pub fn run_soup(seed: &str, symmetry: Symmetry) -> Option<Census> {
+    let mut universe = Universe::new(BOARD_SIZE, BOARD_SIZE);
+    let centre = (BOARD_SIZE - SOUP_SIZE) / 2;
+    universe.paste(
+        &soup::soup(seed, 0.5, SOUP_SIZE, SOUP_SIZE, symmetry),
+        (centre, centre),
+        PasteMode::Or,
+    );
+
+    let mut populations = Vec::with_capacity(SETTLE_LIMIT);
+    for generation in 0..SETTLE_LIMIT {
+        populations.push(universe.population());
+        if generation % census::DEFAULT_MAX_PERIOD == 0 && settled(&populations) {
+            return Some(universe.census());
+        }
+        universe.step();
+    }
+    None
+}
+
+/// true when the population has been repeating with some period for `SETTLE_WINDOW` generations
+fn settled(populations: &[usize]) -> bool {
+    let len = populations.len();
+    (1..=census::DEFAULT_MAX_PERIOD).any(|period| {
+        len >= SETTLE_WINDOW + period
+            && (len - SETTLE_WINDOW..len)
+                .all(|generation| populations[generation] == populations[generation - period])
+    })
+}
+
+/// worth keeping the seed of: anything but still lifes smaller than
+/// `RARE_POPULATION`, oscillators of period 2 or 3 and the glider
+fn is_rare(entry: &CensusEntry) -> bool {
+    match entry.kind {
+        ObjectKind::StillLife => entry
+            .apgcode
+            .parse::<Apgcode>()
+            .ok()
+            .and_then(|apgcode| apgcode.population())
+            .is_none_or(|population| population >= RARE_POPULATION),
+        ObjectKind::Oscillator => entry.period.is_none_or(|period| period > 3),
+        ObjectKind::Spaceship => entry.apgcode != "xq4_153",
+        ObjectKind::Unknown => true,
+    }
+}
+
+/// the census entry for an apgcode read from a checkpoint
+fn entry_for(apgcode: &str) -> Result<CensusEntry, FormatError> {
+    if apgcode.starts_with("zz_") {
+        return Ok(CensusEntry {
+            apgcode: apgcode.to_string(),
+            kind: ObjectKind::Unknown,
+            period: None,
+            count: 0,
+        });
+    }
+
+    let parsed: Apgcode = apgcode.parse()?;
+    Ok(CensusEntry {
+        apgcode: apgcode.to_string(),
+        kind: parsed.kind(),
+        period: Some(parsed.period()),
+        count: 0,
+    })
+}
@@ -0,0 +1,214 @@
+// everything needed to pick a session back up later, as text
+
+use std::fmt::Write;
+use std::io::{self, Read};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use flate2::read::{DeflateDecoder, DeflateEncoder};
+use flate2::Compression;
+
+use crate::formats::{rle, FormatError};
+use crate::universe::pattern::{PasteMode, Pattern};
+use crate::universe::rect::Rect;
+use crate::universe::snapshot::RULE;
+use crate::universe::{Universe, MAX_SIZE};
+
+const HEADER: &str = "#session";
+/// the most RLE a shared link can inflate to, well over what a `MAX_SIZE` board needs
+const MAX_RLE_LENGTH: u64 = 16 << 20;
+
+/// A saved board and the view settings it was shown with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub width: usize,
+    pub height: usize,
+    pub generation: usize,
+    /// the whole board
+    pub pattern: Pattern,
+    /// the side of a cell on the canvas, in pixels
+    pub cell_size: usize,
+    pub steps_per_tick: usize,
+}
+
+impl Session {
+    pub fn capture(universe: &Universe, cell_size: usize, steps_per_tick: usize) -> Session {
+        let (width, height) = (universe.width(), universe.height());
+        Session {
+            width,
+            height,
+            generation: universe.generation(),
+            pattern: universe.copy_region(Rect::new(0, 0, width, height)),
+            cell_size,
+            steps_per_tick,
+        }
+    }
+
+    /// a new universe holding the saved board, cut down to `MAX_SIZE` if it's bigger
+    pub fn universe(&self) -> Universe {
+        let mut universe = Universe::new(self.width.min(MAX_SIZE), self.height.min(MAX_SIZE));
+        universe.paste(&self.pattern, (0, 0), PasteMode::Copy);
+        universe.set_generation(self.generation);
+        universe
+    }
+
+    /// the session as text: a line for each setting, then the board as RLE
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        writeln!(text, "{}", HEADER).unwrap();
+        writeln!(text, "rule {}", RULE).unwrap();
+        writeln!(text, "size {} {}", self.width, self.height).unwrap();
+        writeln!(text, "generation {}", self.generation).unwrap();
+        writeln!(text, "cell-size {}", self.cell_size).unwrap();
+        writeln!(text, "steps-per-tick {}", self.steps_per_tick).unwrap();
+        text.push_str(&rle::write(&self.pattern));
+        text
+    }
+
+    /// the session as a URL fragment (without the `#`), for sharing links.
+    /// the board is RLE, deflated and then base64url encoded
+    pub fn to_fragment(&self) -> String {
+        let rle = rle::write(&self.pattern);
+        let mut deflated = vec![];
+        DeflateEncoder::new(rle.as_bytes(), Compression::best())
+            .read_to_end(&mut deflated)
+            .expect("compressing in memory not to fail");
+
+        format!(
+            "rule={}&size={}x{}&generation={}&cell-size={}&steps-per-tick={}&rle={}",
+            RULE,
+            self.width,
+            self.height,
+            self.generation,
+            self.cell_size,
+            self.steps_per_tick,
+            URL_SAFE_NO_PAD.encode(deflated)
+        )
+    }
+
+    /// reads back what `to_fragment` wrote, with or without the `#`
+    pub fn from_fragment(fragment: &str) -> Result<Session, FormatError> {
+        let fragment = fragment.trim_start_matches('#');
+        let mut size = None;
+        let mut generation = 0;
+        let mut cell_size = 1;
+        let mut steps_per_tick = 1;
+        let mut rle = None;
+
+        for field in fragment.split('&').filter(|field| !field.is_empty()) {
+            let invalid = || FormatError::InvalidHeader(field.to_string());
+            let parse = |value: &str| value.parse::<usize>().map_err(|_| invalid());
+            let (key, value) = field.split_once('=').ok_or_else(invalid)?;
+            match key {
+                "rule" if value == RULE => {}
+                "size" => {
+                    let (width, height) = value.split_once('x').ok_or_else(invalid)?;
+                    size = Some((parse(width)?, parse(height)?));
+                }
+                "generation" => generation = parse(value)?,
+                "cell-size" => cell_size = parse(value)?.max(1),
+                "steps-per-tick" => steps_per_tick = parse(value)?.max(1),
+                "rle" => {
+                    let encoding = |error: &dyn std::fmt::Display| {
+                        FormatError::InvalidEncoding(error.to_string())
+                    };
+                    let deflated = URL_SAFE_NO_PAD
+                        .decode(value)
+                        .map_err(|error| encoding(&error))?;
+                    let mut inflated = String::new();
+                    DeflateDecoder::new(deflated.as_slice())
+                        .take(MAX_RLE_LENGTH + 1)
+                        .read_to_string(&mut inflated)
+                        .map_err(|error: io::Error| encoding(&error))?;
+                    if inflated.len() as u64 > MAX_RLE_LENGTH {
+                        return Err(encoding(&"the board inflates to too much RLE"));
+                    }
+                    rle = Some(inflated);
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        let rle = rle.ok_or(FormatError::UnexpectedEnd { line: 1 })?;
+        let (pattern, width, height) = read_board(&rle, size)?;
+        Ok(Session {
+            width,
+            height,
+            generation,
+            pattern,
+            cell_size,
+            steps_per_tick,
+        })
+    }
+
+    /// reads back what `to_text` wrote
+    pub fn from_text(text: &str) -> Result<Session, FormatError> {
+        let mut lines = text.lines();
+        match lines.next() {
+            Some(header) if header.trim() == HEADER => {}
+            Some(header) => return Err(FormatError::InvalidHeader(header.to_string())),
+            None => return Err(FormatError::UnexpectedEnd { line: 1 }),
+        }
+
+        let mut size = None;
+        let mut generation = 0;
+        let mut cell_size = 1;
+        let mut steps_per_tick = 1;
+        let mut board = None;
+        for (index, line) in lines.enumerate() {
+            let line = line.trim();
+            if line.starts_with('x') {
+                // the rest is the board, starting with this line
+                let rest: Vec<_> = text.lines().skip(index + 1).collect();
+                board = Some(read_board(&rest.join("\n"), size)?);
+                break;
+            }
+
+            let invalid = || FormatError::InvalidHeader(line.to_string());
+            let parse = |value: &str| value.parse::<usize>().map_err(|_| invalid());
+            let words: Vec<_> = line.split_whitespace().collect();
+            match words.as_slice() {
+                [] => {}
+                ["rule", rule] if *rule == RULE => {}
+                ["size", width, height] => size = Some((parse(width)?, parse(height)?)),
+                ["generation", value] => generation = parse(value)?,
+                ["cell-size", value] => cell_size = parse(value)?.max(1),
+                ["steps-per-tick", value] => steps_per_tick = parse(value)?.max(1),
+                _ => return Err(invalid()),
+            }
+        }
+
+        let (pattern, width, height) = board.ok_or(FormatError::UnexpectedEnd {
+            line: text.lines().count(),
+        })?;
+
+        Ok(Session {
+            width,
+            height,
+            generation,
+            pattern,
+            cell_size,
+            steps_per_tick,
+        })
+    }
+}
+
+/// reads the board's RLE, returning it with the size of the board: `size`, or the
+/// pattern's own size if there isn't one. bigger than `MAX_SIZE` is an error, found
+/// before anything is allocated for the board
+fn read_board(
+    rle: &str,
+    size: Option<(usize, usize)>,
+) -> Result<(Pattern, usize, usize), FormatError> {
+    let too_large = |(width, height)| width > MAX_SIZE || height > MAX_SIZE;
+    if let Some((width, height)) = size.filter(|&size| too_large(size)) {
+        return Err(FormatError::TooLarge { width, height });
+    }
+    let (width, height) = size.unwrap_or((MAX_SIZE, MAX_SIZE));
+    let pattern = rle::read_within(rle, width.max(1), height.max(1))?;
+    let (width, height) = size.unwrap_or((pattern.width(), pattern.height()));
+    if too_large((width, height)) {
+        return Err(FormatError::TooLarge { width, height });
+    }
+    Ok((pattern, width.max(1), height.max(1)))
+}
@@ -0,0 +1,87 @@
+// how long each cell in part of the board has been alive
+
+use super::node::NodeId;
+use super::rect::Rect;
+
+/// Generations since each live cell inside a viewport was born.
+///
+/// The quadtree only knows whether a cell is alive, so ages are kept on the side,
+/// for just the part of the board being looked at. Only births and deaths are
+/// written down, as the generation they happened in, so keeping up costs as much
+/// as what changed rather than as much as what's alive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellAges {
+    viewport: Rect,
+    /// the generation each cell was born in, row by row, `None` for dead cells
+    born: Vec<Option<usize>>,
+    generation: usize,
+    /// the root the ages were last brought up to date with
+    pub(super) synced: NodeId,
+}
+
+impl CellAges {
+    /// ages for the cells in `viewport`, with `alive` taken to be born in `generation`
+    pub(super) fn new(
+        viewport: Rect,
+        alive: impl Iterator<Item = (usize, usize)>,
+        generation: usize,
+        synced: NodeId,
+    ) -> Self {
+        let mut ages = CellAges {
+            viewport,
+            born: vec![None; viewport.width() * viewport.height()],
+            generation,
+            synced,
+        };
+        for (x, y) in alive {
+            ages.set_born(x, y, Some(generation));
+        }
+        ages
+    }
+
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        if self.viewport.contains(x, y) {
+            let (vx, vy) = (x - self.viewport.x(), y - self.viewport.y());
+            Some(vy * self.viewport.width() + vx)
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn set_born(&mut self, x: usize, y: usize, born: Option<usize>) {
+        if let Some(index) = self.index(x, y) {
+            self.born[index] = born;
+        }
+    }
+
+    pub(super) fn set_generation(&mut self, generation: usize) {
+        self.generation = generation;
+    }
+
+    /// the part of the board whose ages are kept
+    pub fn viewport(&self) -> Rect {
+        self.viewport
+    }
+
+    /// how many generations ago the cell at `(x, y)` was born, 0 for one born this
+    /// generation. `None` if it's dead or outside of the viewport
+    pub fn get(&self, x: usize, y: usize) -> Option<usize> {
+        let born = self.born[self.index(x, y)?]?;
+        Some(self.generation.saturating_sub(born))
+    }
+
+    /// `(x, y, age)` for every live cell in the viewport
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        let viewport = self.viewport;
+        let width = viewport.width().max(1);
+        self.born.iter().enumerate().filter_map(move |(i, born)| {
+            let age = self.generation.saturating_sub((*born)?);
+            Some((viewport.x() + i % width, viewport.y() + i / width, age))
+        })
+    }
+
+    /// the age of the oldest live cell in the viewport
+    pub fn max(&self) -> Option<usize> {
+        self.cells().map(|(_, _, age)| age).max()
+    }
+}
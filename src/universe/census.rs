@@ -0,0 +1,273 @@
+// splitting settled ash into objects and naming them, the way apgsearch does
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::pattern::Pattern;
+use super::rect::Rect;
+use super::Universe;
+use crate::formats::apgcode::Apgcode;
+
+/// cells this close (in either direction) can affect each other's
+/// neighbourhoods, so they start out as one object
+pub const DEFAULT_DISTANCE: usize = 2;
+
+/// the longest period looked for before giving up on an object
+pub const DEFAULT_MAX_PERIOD: usize = 60;
+
+/// What kind of object a census found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ObjectKind {
+    StillLife,
+    Oscillator,
+    Spaceship,
+    /// didn't repeat within the longest period looked for
+    Unknown,
+}
+
+/// every object with the same apgcode, and how many there were
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CensusEntry {
+    pub apgcode: String,
+    pub kind: ObjectKind,
+    /// `None` for objects of unknown kind
+    pub period: Option<usize>,
+    pub count: usize,
+}
+
+/// The objects in a universe, keyed by apgcode
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Census {
+    entries: BTreeMap<String, CensusEntry>,
+}
+
+impl Census {
+    /// takes a census of the live cells of `universe`, which should have settled.
+    ///
+    /// cells within `distance` of each other are grouped, and each group is run
+    /// on its own for up to `max_period` generations to find its period. a group of
+    /// separate pieces that run the same apart as together and are objects in their
+    /// own right (a pseudo object, like two blocks side by side) counts as each
+    /// piece. objects that cross the edge of a wrapping board are seen as two
+    pub fn take(universe: &Universe, distance: usize, max_period: usize) -> Census {
+        let cells: Vec<_> = universe.live_cells().collect();
+        let mut census = Census::default();
+
+        for group in components(&cells, distance) {
+            let pieces = components(&group, 1);
+            let split = if pieces.len() > 1 && independent(&group, &pieces, max_period) {
+                pieces
+                    .iter()
+                    .map(|piece| classify(piece, max_period))
+                    .collect()
+            } else {
+                vec![]
+            };
+
+            if !split.is_empty()
+                && split
+                    .iter()
+                    .all(|object| object.kind != ObjectKind::Unknown)
+            {
+                for object in split {
+                    census.add(object, 1);
+                }
+            } else {
+                census.add(classify(&group, max_period), 1);
+            }
+        }
+
+        census
+    }
+
+    /// adds `count` more objects named `object.apgcode`, ignoring `object.count`
+    pub fn add(&mut self, object: CensusEntry, count: usize) {
+        self.entries
+            .entry(object.apgcode.clone())
+            .or_insert(CensusEntry { count: 0, ..object })
+            .count += count;
+    }
+
+    pub fn get(&self, apgcode: &str) -> Option<&CensusEntry> {
+        self.entries.get(apgcode)
+    }
+
+    /// every entry, in apgcode order
+    pub fn entries(&self) -> impl Iterator<Item = &CensusEntry> {
+        self.entries.values()
+    }
+
+    /// every entry, most common first
+    pub fn by_frequency(&self) -> Vec<&CensusEntry> {
+        let mut entries: Vec<_> = self.entries.values().collect();
+        entries.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.apgcode.cmp(&b.apgcode))
+        });
+        entries
+    }
+
+    /// how many objects of `kind` were found
+    pub fn count(&self, kind: ObjectKind) -> usize {
+        self.entries
+            .values()
+            .filter(|entry| entry.kind == kind)
+            .map(|entry| entry.count)
+            .sum()
+    }
+
+    /// how many objects were found
+    pub fn total(&self) -> usize {
+        self.entries.values().map(|entry| entry.count).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// splits `cells` into groups where every cell is within `distance` of another in its group
+fn components(cells: &[(usize, usize)], distance: usize) -> Vec<Vec<(usize, usize)>> {
+    let index: HashMap<_, _> = cells
+        .iter()
+        .enumerate()
+        .map(|(i, &cell)| (cell, i))
+        .collect();
+    let mut parent: Vec<usize> = (0..cells.len()).collect();
+
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    for (i, &(x, y)) in cells.iter().enumerate() {
+        for ny in y.saturating_sub(distance)..=y + distance {
+            for nx in x.saturating_sub(distance)..=x + distance {
+                if let Some(&j) = index.get(&(nx, ny)) {
+                    let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                    parent[a] = b;
+                }
+            }
+        }
+    }
+
+    let mut groups: BTreeMap<usize, Vec<(usize, usize)>> = BTreeMap::new();
+    for (i, &cell) in cells.iter().enumerate() {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().push(cell);
+    }
+    groups.into_values().collect()
+}
+
+/// runs `cells` on a scratch board around `bounds` for up to `generations`,
+/// yielding the live cells of each generation as it gets to it, so callers can
+/// stop early. the board has enough room that nothing moving at the speed of
+/// light reaches the edge
+fn evolve(
+    cells: &[(usize, usize)],
+    bounds: Rect,
+    generations: usize,
+) -> impl Iterator<Item = Vec<(usize, usize)>> {
+    let margin = generations + 2;
+    let mut scratch = Universe::new(bounds.width() + 2 * margin, bounds.height() + 2 * margin);
+    let shifted: Vec<_> = cells
+        .iter()
+        .map(|&(x, y)| (x - bounds.x() + margin, y - bounds.y() + margin))
+        .collect();
+    scratch.set_cells(&shifted);
+
+    (0..=generations).map(move |generation| {
+        if generation != 0 {
+            scratch.step();
+        }
+        let mut live: Vec<_> = scratch.live_cells().collect();
+        live.sort_unstable();
+        live
+    })
+}
+
+/// the live cells of every generation, see `evolve`
+fn history(cells: &[(usize, usize)], bounds: Rect, generations: usize) -> Vec<Vec<(usize, usize)>> {
+    evolve(cells, bounds, generations).collect()
+}
+
+fn bounds_of(cells: &[(usize, usize)]) -> Rect {
+    let left = cells.iter().map(|&(x, _)| x).min().unwrap_or(0);
+    let top = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+    let right = cells.iter().map(|&(x, _)| x + 1).max().unwrap_or(0);
+    let bottom = cells.iter().map(|&(_, y)| y + 1).max().unwrap_or(0);
+    Rect::new(left, top, right - left, bottom - top)
+}
+
+/// true when each piece runs exactly as it does with the others around
+fn independent(
+    group: &[(usize, usize)],
+    pieces: &[Vec<(usize, usize)>],
+    generations: usize,
+) -> bool {
+    let bounds = bounds_of(group);
+    let together = history(group, bounds, generations);
+
+    let mut apart = vec![vec![]; generations + 1];
+    for piece in pieces {
+        for (generation, cells) in history(piece, bounds, generations).into_iter().enumerate() {
+            apart[generation].extend(cells);
+        }
+    }
+    for cells in apart.iter_mut() {
+        cells.sort_unstable();
+    }
+
+    together == apart
+}
+
+/// the cells relative to their own bounding box, so moved copies compare equal
+fn shape(cells: &[(usize, usize)]) -> (Rect, Pattern) {
+    let bounds = bounds_of(cells);
+    let relative: Vec<_> = cells
+        .iter()
+        .map(|&(x, y)| (x - bounds.x(), y - bounds.y()))
+        .collect();
+    let pattern = Pattern::with_cells(bounds.width(), bounds.height(), &relative);
+    (bounds, pattern)
+}
+
+/// the apgcode of `cells` run on their own, `None` if they don't repeat within `max_period`
+pub(crate) fn identify(cells: &[(usize, usize)], max_period: usize) -> Option<Apgcode> {
+    if cells.is_empty() {
+        return None;
+    }
+
+    // stops at the first repeat, after a single step for still lifes
+    let mut phases = evolve(cells, bounds_of(cells), max_period).map(|cells| shape(&cells));
+    let (start, first) = phases.next()?;
+    let mut patterns = vec![first];
+    for (end, pattern) in phases {
+        if pattern == patterns[0] {
+            let moved = (end.x(), end.y()) != (start.x(), start.y());
+            return Some(Apgcode::from_phases(&patterns, moved));
+        }
+        patterns.push(pattern);
+    }
+    None
+}
+
+fn classify(cells: &[(usize, usize)], max_period: usize) -> CensusEntry {
+    match identify(cells, max_period) {
+        Some(apgcode) => CensusEntry {
+            kind: apgcode.kind(),
+            period: Some(apgcode.period()),
+            apgcode: apgcode.to_string(),
+            count: 1,
+        },
+        None => CensusEntry {
+            apgcode: "zz_UNKNOWN".to_string(),
+            kind: ObjectKind::Unknown,
+            period: None,
+            count: 1,
+        },
+    }
+}
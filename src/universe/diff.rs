@@ -0,0 +1,68 @@
+// what changed between two boards, see `Universe::diff`
+
+use super::rect::{self, Rect};
+
+/// The cells born and the cells that died between two boards, each row by row
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Diff {
+    births: Vec<(usize, usize)>,
+    deaths: Vec<(usize, usize)>,
+}
+
+impl Diff {
+    pub fn new(mut births: Vec<(usize, usize)>, mut deaths: Vec<(usize, usize)>) -> Self {
+        births.sort_unstable_by_key(|&(x, y)| (y, x));
+        deaths.sort_unstable_by_key(|&(x, y)| (y, x));
+        Diff { births, deaths }
+    }
+
+    /// cells dead before and alive after
+    pub fn births(&self) -> &[(usize, usize)] {
+        &self.births
+    }
+
+    /// cells alive before and dead after
+    pub fn deaths(&self) -> &[(usize, usize)] {
+        &self.deaths
+    }
+
+    /// true when the boards are the same
+    pub fn is_empty(&self) -> bool {
+        self.births.is_empty() && self.deaths.is_empty()
+    }
+
+    /// how many cells changed
+    pub fn len(&self) -> usize {
+        self.births.len() + self.deaths.len()
+    }
+
+    /// every cell that changed, births first
+    pub fn changed(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.births.iter().chain(self.deaths.iter()).copied()
+    }
+
+    /// the births merged into rectangles, see `rect::cover`
+    pub fn birth_rects(&self) -> Vec<Rect> {
+        rect::cover(&mut self.births.clone())
+    }
+
+    /// the deaths merged into rectangles
+    pub fn death_rects(&self) -> Vec<Rect> {
+        rect::cover(&mut self.deaths.clone())
+    }
+
+    /// every changed cell merged into rectangles, for redrawing just those parts
+    pub fn rects(&self) -> Vec<Rect> {
+        rect::cover(&mut self.changed().collect::<Vec<_>>())
+    }
+
+    /// the smallest rectangle holding every change, `None` if nothing changed
+    pub fn bounding_box(&self) -> Option<Rect> {
+        let mut cells = self.changed();
+        let (x, y) = cells.next()?;
+        let (left, top, right, bottom) = cells.fold((x, y, x, y), |(l, t, r, b), (x, y)| {
+            (l.min(x), t.min(y), r.max(x), b.max(y))
+        });
+        Some(Rect::new(left, top, right - left + 1, bottom - top + 1))
+    }
+}
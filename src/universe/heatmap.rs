@@ -0,0 +1,134 @@
+// how often each cell has changed over the last few generations
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+/// how many generations a heatmap covers unless told otherwise
+pub const DEFAULT_WINDOW: usize = 100;
+
+/// Per cell counts of how many times a cell was born or died, over a sliding
+/// window of the most recent generations.
+///
+/// Still lifes and empty space stay at 0, rotors count up with their period and
+/// glider lanes and chaotic regions light up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heatmap {
+    width: usize,
+    height: usize,
+    window: usize,
+    /// row by row, `width` to a row
+    counts: Vec<u32>,
+    /// the cells that changed in each generation still in the window, oldest first
+    changes: VecDeque<Vec<(usize, usize)>>,
+}
+
+impl Heatmap {
+    /// an empty heatmap for a `width` x `height` board, covering `window` generations
+    pub fn new(width: usize, height: usize, window: usize) -> Self {
+        Heatmap {
+            width,
+            height,
+            window: window.max(1),
+            counts: vec![0; width * height],
+            changes: VecDeque::new(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// how many generations are kept
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// how many generations have been recorded, up to the window
+    pub fn generations(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// adds a generation's changed cells, dropping the oldest one once the window is full.
+    /// cells off the board are ignored
+    pub fn record(&mut self, mut changed: Vec<(usize, usize)>) {
+        changed.retain(|&(x, y)| x < self.width && y < self.height);
+        for &(x, y) in &changed {
+            self.counts[y * self.width + x] += 1;
+        }
+        self.changes.push_back(changed);
+
+        while self.changes.len() > self.window {
+            self.forget_oldest();
+        }
+    }
+
+    fn forget_oldest(&mut self) {
+        if let Some(oldest) = self.changes.pop_front() {
+            for (x, y) in oldest {
+                self.counts[y * self.width + x] -= 1;
+            }
+        }
+    }
+
+    /// changes how many generations are kept, forgetting the oldest if it shrinks
+    pub fn set_window(&mut self, window: usize) {
+        self.window = window.max(1);
+        while self.changes.len() > self.window {
+            self.forget_oldest();
+        }
+    }
+
+    /// forgets everything recorded
+    pub fn clear(&mut self) {
+        self.counts.iter_mut().for_each(|count| *count = 0);
+        self.changes.clear();
+    }
+
+    /// how many times the cell at `(x, y)` changed in the window, 0 off the board
+    pub fn get(&self, x: usize, y: usize) -> u32 {
+        if x < self.width && y < self.height {
+            self.counts[y * self.width + x]
+        } else {
+            0
+        }
+    }
+
+    /// the counts row by row, `width` to a row
+    pub fn counts(&self) -> &[u32] {
+        &self.counts
+    }
+
+    /// the highest count, for scaling colors
+    pub fn max(&self) -> u32 {
+        self.counts.iter().copied().max().unwrap_or(0)
+    }
+
+    /// `(x, y, count)` for every cell that changed at all
+    pub fn active_cells(&self) -> impl Iterator<Item = (usize, usize, u32)> + '_ {
+        let width = self.width;
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(move |(i, &count)| (i % width, i / width, count))
+    }
+
+    /// the counts as a matrix of comma separated rows, for spreadsheets and plotting
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::with_capacity(self.counts.len() * 2);
+        for row in self.counts.chunks(self.width.max(1)) {
+            for (x, count) in row.iter().enumerate() {
+                if x > 0 {
+                    csv.push(',');
+                }
+                write!(csv, "{}", count).unwrap();
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+}
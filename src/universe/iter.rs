@@ -0,0 +1,77 @@
+use std::ops::Deref;
+
+use super::morton;
+use super::node::NodeId;
+use super::rect::Rect;
+use super::Universe;
+
+/// Iterator over the `(x, y)` coordinates of the live cells of a `Universe`, in morton order.
+///
+/// Walks the quadtree and skips any node that is empty or outside of the bounds,
+/// so the work done is proportional to the population rather than the area.
+pub struct LiveCells<'a> {
+    universe: &'a Universe,
+    bounds: Rect,
+    stack: Vec<(NodeId, usize, usize)>,
+    leaf: Vec<(usize, usize)>,
+}
+
+impl<'a> LiveCells<'a> {
+    pub fn new(universe: &'a Universe, root: NodeId, bounds: Rect) -> Self {
+        LiveCells {
+            universe,
+            bounds,
+            stack: vec![(root, 0, 0)],
+            leaf: vec![],
+        }
+    }
+
+    fn overlaps(&self, x: usize, y: usize, n: usize) -> bool {
+        x < self.bounds.right()
+            && y < self.bounds.bottom()
+            && x + n > self.bounds.x()
+            && y + n > self.bounds.y()
+    }
+}
+
+impl<'a> Iterator for LiveCells<'a> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(cell) = self.leaf.pop() {
+                return Some(cell);
+            }
+
+            let (id, x, y) = self.stack.pop()?;
+            let node = self.universe.get_node(id);
+            let n = node.rect().width();
+
+            if node.population() == 0 || !self.overlaps(x, y, n) {
+                continue;
+            }
+
+            if let Some(children) = node.children() {
+                // pushed backwards so the nw child comes off the stack first
+                let half = n / 2;
+                self.stack.push((children.se(), x + half, y + half));
+                self.stack.push((children.sw(), x, y + half));
+                self.stack.push((children.deref().ne(), x + half, y));
+                self.stack.push((children.nw(), x, y));
+            } else {
+                let bounds = self.bounds;
+                self.leaf = node
+                    .space()
+                    .iter_ones()
+                    .map(|index| {
+                        let (cx, cy) = morton::unravel_point(index);
+                        (x + cx, y + cy)
+                    })
+                    .filter(|&(cx, cy)| bounds.contains(cx, cy))
+                    .collect();
+                // popped off the back, so the first cell has to go last
+                self.leaf.reverse();
+            }
+        }
+    }
+}
@@ -1,24 +1,67 @@
 use std::collections::HashMap;
+use std::convert::TryFrom;
 
 use std::ops::Deref;
 
 extern crate rand;
 
-#[cfg(feature = "no-wasm")]
 extern crate rand_chacha;
 #[cfg(feature = "no-wasm")]
 use rand::{Rng, SeedableRng};
 
 // use bitvec::prelude::*;
 
+pub mod ages;
+pub mod census;
+pub mod diff;
+pub mod heatmap;
+pub mod iter;
 pub mod morton;
 pub mod node;
+pub mod pattern;
 pub mod rect;
-
+pub mod snapshot;
+pub mod soup;
+pub mod timeline;
+
+use super::formats::apgcode::Apgcode;
+use super::formats::{self, rle, Format, FormatError, LoadedPattern};
+use super::universe::ages::CellAges;
+use super::universe::census::Census;
+use super::universe::diff::Diff;
+use super::universe::heatmap::Heatmap;
+use super::universe::iter::LiveCells;
 use super::universe::node::{BitSpace, BitSpaceSlice, Node, NodeId, SubNode};
+use super::universe::pattern::{PasteMode, Pattern, Transform};
+use super::universe::rect::Rect;
+use super::universe::soup::Symmetry;
 
 type NodeMap = HashMap<Node, NodeId>;
 
+/// Which part of the board stays put when it is resized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Center,
+}
+
+/// the widest and tallest board a saved or shared session or the size inputs can
+/// ask for, so neither can take more memory than a browser tab has
+pub const MAX_SIZE: usize = 2048;
+
+/// A `width` x `height` board on a torus.
+///
+/// The quadtree always covers a power of two square (`size()`), with the board
+/// in its top left corner. Cells outside of the board are kept dead and the
+/// edges of the board wrap onto each other when stepping.
+///
+/// Cells are addressed as `(x, y)`: `x` is the column, growing east (right),
+/// and `y` is the row, growing south (down), with `(0, 0)` the top left cell.
+/// Every method that takes or returns cell coordinates uses this order.
 #[derive(Debug, Clone)]
 pub struct Universe {
     width: usize,
@@ -29,41 +72,61 @@ pub struct Universe {
     empty_node_map: HashMap<(usize, usize), NodeId>,
     non_empty_node_map: HashMap<Vec<u8>, NodeId>,
     next_node_map: HashMap<NodeId, NodeId>,
+    /// results of `jump_node` for more than one generation, by node and power of two
+    jump_node_map: HashMap<(NodeId, usize), NodeId>,
     morton_space: morton::MortonSpace,
+    /// how many times the board has been stepped since it was made or reset
+    generation: usize,
+    /// every cell that has been alive since history tracking was turned on,
+    /// a root sized node like `root`. `None` when history isn't being tracked
+    envelope: Option<NodeId>,
+    /// how often each cell changed over the last few generations, when tracked
+    heatmap: Option<Heatmap>,
+    /// how long the cells in a viewport have been alive, when tracked
+    ages: Option<CellAges>,
 }
 
 impl Universe {
     const MIN_NODE_WIDTH: usize = 4;
     const MIN_NODE_HEIGHT: usize = 4;
+    // `slow_sim` works on 16x16 nodes, so the root (and the morton cache) must be at least that big
+    const MIN_ROOT_SIZE: usize = 16;
 
     pub fn new(width: usize, height: usize) -> Self {
-        let w = if width % 2 != 0 { width + 1 } else { width };
-        let h = if height % 2 != 0 { height + 1 } else { height };
-
-        if width % 2 != 0 {
-            width + 1
-        } else {
-            width
-        };
+        let (width, height) = (width.max(1), height.max(1));
+        let size = Self::root_size_for(width, height);
 
         let mut universe = Universe {
-            width: w,
-            height: h,
+            width,
+            height,
             root: NodeId::new(0),
-            arena: Vec::with_capacity(w),
+            arena: Vec::with_capacity(size),
             node_map: HashMap::new(),
             empty_node_map: HashMap::new(),
             non_empty_node_map: HashMap::new(),
             next_node_map: HashMap::new(),
-            morton_space: morton::MortonSpace::new(width, height),
+            jump_node_map: HashMap::new(),
+            morton_space: morton::MortonSpace::new(size, size),
+            generation: 0,
+            envelope: None,
+            heatmap: None,
+            ages: None,
         };
 
-        let root = universe.node(width, height);
+        let root = universe.node(size, size);
         universe.root = root;
 
         universe
     }
 
+    /// side of the power of two square root node that holds a `width` x `height` board
+    fn root_size_for(width: usize, height: usize) -> usize {
+        width
+            .max(height)
+            .max(Self::MIN_ROOT_SIZE)
+            .next_power_of_two()
+    }
+
     fn canonicalize(&mut self, node: Box<Node>) -> NodeId {
         if let Some(canon) = self.node_map.get(&node) {
             return *canon;
@@ -152,6 +215,11 @@ impl Universe {
             .deref()
     }
 
+    /// how many cells are alive
+    pub fn population(&self) -> usize {
+        self.get_population(self.root)
+    }
+
     pub fn get_population(&self, id: NodeId) -> usize {
         self.get_node(id).population()
     }
@@ -173,8 +241,9 @@ impl Universe {
         self.get_node(children.nw()).level()
     }
 
-    pub fn get_morton(&self, row: usize, col: usize) -> usize {
-        self.morton_space.morton2(row, col)
+    /// index of the cell at `(x, y)` in the bit space returned by `get_cells`
+    pub fn get_morton(&self, x: usize, y: usize) -> usize {
+        self.morton_space.morton2(x, y)
     }
 
     pub fn width(&self) -> usize {
@@ -185,35 +254,462 @@ impl Universe {
         self.height
     }
 
+    /// side of the square the board is embedded in
+    pub fn size(&self) -> usize {
+        self.get_node(self.root).rect().width()
+    }
+
     pub fn set_width(&mut self, width: usize) {
-        self.width = width;
-        self.reset();
+        self.resize(width, self.height, Anchor::default());
     }
 
     pub fn set_height(&mut self, height: usize) {
+        self.resize(self.width, height, Anchor::default());
+    }
+
+    /// changes the size of the board, keeping the cells at `anchor` where they are.
+    /// anything that no longer fits on the board is cut off
+    pub fn resize(&mut self, width: usize, height: usize, anchor: Anchor) {
+        let (width, height) = (width.max(1), height.max(1));
+        let dw = width as isize - self.width as isize;
+        let dh = height as isize - self.height as isize;
+
+        let dx = match anchor {
+            Anchor::TopLeft | Anchor::BottomLeft => 0,
+            Anchor::TopRight | Anchor::BottomRight => dw,
+            Anchor::Center => dw / 2,
+        };
+        let dy = match anchor {
+            Anchor::TopLeft | Anchor::TopRight => 0,
+            Anchor::BottomLeft | Anchor::BottomRight => dh,
+            Anchor::Center => dh / 2,
+        };
+
+        self.reframe(-dx, -dy, width, height);
+    }
+
+    /// shrinks the board down to the smallest rectangle holding every live cell.
+    /// an empty board is left alone
+    pub fn crop_to_bounding_box(&mut self) {
+        if let Some(bounds) = self.bounding_box() {
+            self.reframe(
+                bounds.x() as isize,
+                bounds.y() as isize,
+                bounds.width(),
+                bounds.height(),
+            );
+        }
+    }
+
+    /// smallest rectangle holding every live cell, `None` if there aren't any
+    pub fn bounding_box(&self) -> Option<Rect> {
+        let mut bounds = None;
+        self.node_bounds(self.root, 0, 0, &mut bounds);
+
+        bounds.map(|(left, top, right, bottom)| Rect::new(left, top, right - left, bottom - top))
+    }
+
+    /// every live cell on the board as `(x, y)`
+    pub fn live_cells(&self) -> LiveCells<'_> {
+        LiveCells::new(self, self.root, Rect::new(0, 0, self.width, self.height))
+    }
+
+    /// the live cells inside `rect` as `(x, y)`, in board coordinates
+    pub fn live_cells_in(&self, rect: Rect) -> LiveCells<'_> {
+        LiveCells::new(self, self.root, rect)
+    }
+
+    /// the cells inside `rect` as a pattern the size of `rect`, clipped to the board
+    pub fn copy_region(&self, rect: Rect) -> Pattern {
+        let cells: Vec<_> = self
+            .live_cells_in(rect)
+            .map(|(x, y)| (x - rect.x(), y - rect.y()))
+            .collect();
+
+        Pattern::with_cells(rect.width(), rect.height(), &cells)
+    }
+
+    /// kills every cell inside `rect`
+    pub fn clear_region(&mut self, rect: Rect) {
+        let size = self.size();
+        let empty = self.node(size, size);
+        self.paste_rect(empty, rect, PasteMode::Copy);
+    }
+
+    /// copies `pattern` onto the board with its top left corner at `at`,
+    /// combining it with what is there according to `mode`.
+    /// the parts of the pattern that fall off the board are dropped
+    pub fn paste(&mut self, pattern: &Pattern, at: (usize, usize), mode: PasteMode) {
+        let (w, h) = (self.width, self.height);
+        let cells: Vec<_> = pattern
+            .cells()
+            .iter()
+            .map(|&(x, y)| (x + at.0, y + at.1))
+            .filter(|&(x, y)| x < w && y < h)
+            .collect();
+
+        let size = self.size();
+        let empty = self.node(size, size);
+        let placed = self.set_bits(empty, &cells, true);
+
+        let rect = Rect::new(at.0, at.1, pattern.width(), pattern.height());
+        self.paste_rect(placed, rect, mode);
+    }
+
+    /// pastes `pattern` rotated or flipped by `transform`, see `paste`. the pattern
+    /// is transformed as cells and then pasted like any other, see `Pattern` for why
+    pub fn paste_transformed(
+        &mut self,
+        pattern: &Pattern,
+        at: (usize, usize),
+        transform: Transform,
+        mode: PasteMode,
+    ) {
+        self.paste(&pattern.transform(transform), at, mode);
+    }
+
+    /// merges the part of a root sized node inside `rect` into the root
+    fn paste_rect(&mut self, placed: NodeId, rect: Rect, mode: PasteMode) {
+        let board = Rect::new(0, 0, self.width, self.height);
+        if let Some(rect) = rect.intersection(&board) {
+            self.root = self.paste_node(self.root, placed, 0, 0, rect, mode);
+            self.record_history();
+            self.record_ages();
+        }
+    }
+
+    /// merges `placed` into `target` inside `rect`, both nodes sitting at `(x, y)`
+    fn paste_node(
+        &mut self,
+        target: NodeId,
+        placed: NodeId,
+        x: usize,
+        y: usize,
+        rect: Rect,
+        mode: PasteMode,
+    ) -> NodeId {
+        let node = self.get_node(target);
+        let n = node.rect().width();
+
+        // outside of the rectangle nothing changes
+        if x >= rect.right() || y >= rect.bottom() || x + n <= rect.x() || y + n <= rect.y() {
+            return target;
+        }
+
+        // whole nodes inside of the rectangle can often be merged without looking at them
+        let inside =
+            x >= rect.x() && y >= rect.y() && x + n <= rect.right() && y + n <= rect.bottom();
+        if inside {
+            let target_empty = node.population() == 0;
+            let placed_empty = self.get_population(placed) == 0;
+            match mode {
+                PasteMode::Copy => return placed,
+                PasteMode::Or | PasteMode::Xor if placed_empty => return target,
+                PasteMode::Or | PasteMode::Xor if target_empty => return placed,
+                PasteMode::Or | PasteMode::And if target == placed => return target,
+                PasteMode::Xor if target == placed => return self.node(n, n),
+                PasteMode::And if target_empty => return target,
+                PasteMode::And if placed_empty => return placed,
+                _ => {}
+            }
+        }
+
+        if let Some(children) = node.children().clone() {
+            let placed_children = self.get_children(placed);
+            let half = n / 2;
+
+            let nw = self.paste_node(children.nw(), placed_children.nw(), x, y, rect, mode);
+            let ne = self.paste_node(
+                children.deref().ne(),
+                placed_children.deref().ne(),
+                x + half,
+                y,
+                rect,
+                mode,
+            );
+            let sw = self.paste_node(children.sw(), placed_children.sw(), x, y + half, rect, mode);
+            let se = self.paste_node(
+                children.se(),
+                placed_children.se(),
+                x + half,
+                y + half,
+                rect,
+                mode,
+            );
+
+            self.node_with_children(n, n, nw, ne, sw, se)
+        } else {
+            let mut space = node.space();
+            let placed_space = self.get_node(placed).space();
+            for index in 0..space.len() {
+                let (cx, cy) = morton::unravel_point(index);
+                if rect.contains(x + cx, y + cy) {
+                    let alive = mode.apply(space[index], placed_space[index]);
+                    space.set(index, alive);
+                }
+            }
+            self.node_with_bits(n, n, &space)
+        }
+    }
+
+    /// grows `bounds` (left, top, right, bottom) to take in the live cells of a node at `(x, y)`
+    fn node_bounds(
+        &self,
+        id: NodeId,
+        x: usize,
+        y: usize,
+        bounds: &mut Option<(usize, usize, usize, usize)>,
+    ) {
+        let node = self.get_node(id);
+        let n = node.rect().width();
+
+        if node.population() == 0 {
+            return;
+        }
+        // nothing in here can make the bounds any bigger
+        if let Some((left, top, right, bottom)) = *bounds {
+            if x >= left && y >= top && x + n <= right && y + n <= bottom {
+                return;
+            }
+        }
+
+        if let Some(children) = node.children() {
+            let half = n / 2;
+            self.node_bounds(children.nw(), x, y, bounds);
+            self.node_bounds(children.deref().ne(), x + half, y, bounds);
+            self.node_bounds(children.sw(), x, y + half, bounds);
+            self.node_bounds(children.se(), x + half, y + half, bounds);
+        } else {
+            for index in node.space().iter_ones() {
+                let (cx, cy) = morton::unravel_point(index);
+                let (cx, cy) = (x + cx, y + cy);
+                *bounds = Some(match *bounds {
+                    Some((left, top, right, bottom)) => (
+                        left.min(cx),
+                        top.min(cy),
+                        right.max(cx + 1),
+                        bottom.max(cy + 1),
+                    ),
+                    None => (cx, cy, cx + 1, cy + 1),
+                });
+            }
+        }
+    }
+
+    /// the root node of the board as it is now. node ids stay valid for as long as
+    /// the universe does, so old roots can be kept around and compared with `diff`
+    pub fn root(&self) -> NodeId {
+        self.root
+    }
+
+    /// the cells born and the cells that died going from the board `before` to the
+    /// board `after`, both being roots of this universe at its current size.
+    ///
+    /// Identical parts of the two boards are the same node, so only the branches
+    /// where the child ids differ are looked at and the work is proportional to what
+    /// changed rather than to the size of the board.
+    pub fn diff(&self, before: NodeId, after: NodeId) -> Diff {
+        assert_eq!(
+            self.get_node(before).rect(),
+            self.get_node(after).rect(),
+            "diff needs two roots of the same size"
+        );
+        let (mut births, mut deaths) = (vec![], vec![]);
+        self.diff_node(before, after, 0, 0, &mut births, &mut deaths);
+        Diff::new(births, deaths)
+    }
+
+    /// adds the differences between two same sized nodes at `(x, y)` to `births` and `deaths`
+    fn diff_node(
+        &self,
+        before: NodeId,
+        after: NodeId,
+        x: usize,
+        y: usize,
+        births: &mut Vec<(usize, usize)>,
+        deaths: &mut Vec<(usize, usize)>,
+    ) {
+        if before == after {
+            return;
+        }
+        let (old, new) = (self.get_node(before), self.get_node(after));
+        let n = old.rect().width();
+
+        match (old.children(), new.children()) {
+            (Some(old), Some(new)) => {
+                let half = n / 2;
+                self.diff_node(old.nw(), new.nw(), x, y, births, deaths);
+                let (old_ne, new_ne) = (old.deref().ne(), new.deref().ne());
+                self.diff_node(old_ne, new_ne, x + half, y, births, deaths);
+                self.diff_node(old.sw(), new.sw(), x, y + half, births, deaths);
+                self.diff_node(old.se(), new.se(), x + half, y + half, births, deaths);
+            }
+            _ => {
+                let (old, new) = (old.space(), new.space());
+                for (index, (was, is)) in old.iter().zip(new.iter()).enumerate() {
+                    if was != is {
+                        let (cx, cy) = morton::unravel_point(index);
+                        let cell = (x + cx, y + cy);
+                        if *is {
+                            births.push(cell);
+                        } else {
+                            deaths.push(cell);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// makes a `width` x `height` board whose top left cell is `(x, y)` on the current one
+    fn reframe(&mut self, x: isize, y: isize, width: usize, height: usize) {
+        let size = Self::root_size_for(width, height);
+
+        let root = self.window(self.root, x, y, size);
+        self.width = width;
         self.height = height;
-        self.reset();
+        self.root = self.mask_node(root, width, height);
+        if let Some(envelope) = self.envelope {
+            let envelope = self.window(envelope, x, y, size);
+            self.envelope = Some(self.mask_node(envelope, width, height));
+        }
+        // the counts are for cells that aren't where they were, so start over
+        if let Some(heatmap) = self.heatmap.as_mut() {
+            *heatmap = Heatmap::new(width, height, heatmap.window());
+        }
+        if let Some(viewport) = self.ages.as_ref().map(CellAges::viewport) {
+            self.set_tracking_ages(Some(viewport));
+        }
+        self.morton_space = morton::MortonSpace::new(size, size);
+    }
+
+    /// true when the board covers the whole root so it can wrap without masking
+    fn fills_root(&self) -> bool {
+        let size = self.size();
+        self.width == size && self.height == size
     }
 
     pub fn fill_cells_random(&mut self) {
-        let mut space: BitSpace = BitSpace::with_capacity(self.width * self.height);
+        let size = self.size();
+        let mut space: BitSpace = BitSpace::with_capacity(size * size);
 
         #[cfg(feature = "no-wasm")]
-        {
-            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(10);
-            for _ in 0..(self.width * self.height) {
-                space.push(rng.gen());
-            }
-        }
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(10);
+        #[cfg(feature = "no-wasm")]
+        let mut random_bit = move || rng.gen::<bool>();
 
         #[cfg(not(feature = "no-wasm"))]
-        {
-            for _ in 0..(self.width * self.height) {
-                space.push(rand::random());
+        let random_bit = rand::random::<bool>;
+
+        for index in 0..(size * size) {
+            let (x, y) = morton::unravel_point(index);
+            space.push(x < self.width && y < self.height && random_bit());
+        }
+
+        self.root = self.node_with_bits(size, size, &space);
+        self.record_history();
+        self.record_ages();
+    }
+
+    /// fills `region` (the whole board for `None`) with a soup, see `soup::soup`.
+    /// the soup is as big as the symmetry allows and sits in the top left of the region
+    pub fn randomize_with(
+        &mut self,
+        seed: &str,
+        density: f64,
+        region: Option<Rect>,
+        symmetry: Symmetry,
+    ) {
+        let board = Rect::new(0, 0, self.width, self.height);
+        let region = match region.map_or(Some(board), |region| region.intersection(&board)) {
+            Some(region) => region,
+            None => return,
+        };
+
+        let pattern = soup::soup(seed, density, region.width(), region.height(), symmetry);
+        self.paste(&pattern, (region.x(), region.y()), PasteMode::Copy);
+    }
+
+    /// reads a pattern in any supported format (see `formats::load_pattern`) and
+    /// adds it to the board, with the file's `(0, 0)` at `at`. nothing is changed
+    /// if any live cell would land off the board
+    pub fn load_pattern(&mut self, text: &str, at: (isize, isize)) -> Result<(), FormatError> {
+        // RLE runs can ask for far more cells than the text is long, so they're stopped
+        // at the edge of the board while they're read. the other formats spell out
+        // every cell, so they can't outgrow the text and are checked once read
+        let loaded = match Format::sniff(text) {
+            Format::Rle => {
+                // how far the pattern can reach from its `(0, 0)` before it's off the board
+                let room = |start: isize, size: usize| match usize::try_from(start) {
+                    Ok(start) => size.saturating_sub(start),
+                    Err(_) => size.saturating_add(start.unsigned_abs()),
+                };
+                let (width, height) = (room(at.0, self.width), room(at.1, self.height));
+                rle::read_within(text, width, height)
+                    .map(LoadedPattern::from)
+                    .map_err(|error| match error {
+                        FormatError::OutOfRange { x, y } => FormatError::OutOfRange {
+                            x: x.saturating_add(at.0),
+                            y: y.saturating_add(at.1),
+                        },
+                        error => error,
+                    })?
+            }
+            format => format.read(text)?,
+        };
+        // `None` where the pattern is so far out it doesn't fit in an `isize`
+        let (left, top) = (
+            at.0.checked_add(loaded.origin.0),
+            at.1.checked_add(loaded.origin.1),
+        );
+        let place =
+            |start: Option<isize>, by: usize| start.and_then(|start| formats::offset(start, by));
+
+        let off_board = loaded.pattern.cells().iter().find_map(|&(x, y)| {
+            match (place(left, x), place(top, y)) {
+                (Some(x), Some(y))
+                    if x >= 0
+                        && y >= 0
+                        && (x as usize) < self.width
+                        && (y as usize) < self.height =>
+                {
+                    None
+                }
+                (x, y) => Some(FormatError::OutOfRange {
+                    x: x.unwrap_or(isize::MAX),
+                    y: y.unwrap_or(isize::MAX),
+                }),
             }
+        });
+        if let Some(error) = off_board {
+            return Err(error);
         }
 
-        self.root = self.node_with_bits(self.width, self.height, &space);
+        if let (false, Some(left), Some(top)) = (loaded.pattern.is_empty(), left, top) {
+            self.paste(
+                &loaded.pattern,
+                (left as usize, top as usize),
+                PasteMode::Or,
+            );
+        }
+        Ok(())
+    }
+
+    /// the objects on the board, see `Census::take`
+    pub fn census(&self) -> Census {
+        Census::take(self, census::DEFAULT_DISTANCE, census::DEFAULT_MAX_PERIOD)
+    }
+
+    /// names the object in `region`, run on its own. `None` if the region is empty
+    /// or it doesn't settle into a still life, oscillator or spaceship
+    pub fn get_apgcode(&self, region: Rect) -> Option<Apgcode> {
+        let cells: Vec<_> = self.live_cells_in(region).collect();
+        census::identify(&cells, census::DEFAULT_MAX_PERIOD)
+    }
+
+    /// clears the object's bounding box at `at` and places it there
+    pub fn set_apgcode(&mut self, apgcode: &Apgcode, at: (usize, usize)) {
+        self.paste(&apgcode.pattern(), at, PasteMode::Copy);
     }
 
     pub fn get_cells(&self) -> BitSpace {
@@ -239,7 +735,7 @@ impl Universe {
             self.build_bitspace_from_node((*children).sw(), space_out);
             self.build_bitspace_from_node((*children).se(), space_out);
         } else {
-            space_out.extend(node.space().into_iter());
+            space_out.extend(node.space().iter());
         }
     }
 
@@ -265,55 +761,58 @@ impl Universe {
         BitSpace::from_vec(elems)
     }
 
+    /// turns on every `(x, y)` cell in `cells`, cells off the board are ignored
     pub fn set_cells(&mut self, cells: &[(usize, usize)]) {
-        let mut space: BitSpace = BitSpace::with_capacity(self.width * self.height);
-        self.build_bitspace_from_node(self.root, &mut space);
-
-        for (row, col) in cells.iter().cloned() {
-            let idx = self.get_morton(row, col);
-            space.set(idx, true);
-        }
-
-        self.root = self.node_with_bits(self.width, self.height, &space);
+        let (w, h) = (self.width, self.height);
+        let cells: Vec<_> = cells
+            .iter()
+            .cloned()
+            .filter(|&(x, y)| x < w && y < h)
+            .collect();
+
+        self.root = self.set_bits(self.root, &cells, true);
+        self.record_history();
+        self.record_ages();
     }
 
-    pub fn get_cell(&self, row: usize, col: usize) -> Result<bool, &'static str> {
-        self.get_cell_node(row, col, self.root)
+    pub fn get_cell(&self, x: usize, y: usize) -> Result<bool, &'static str> {
+        if x >= self.width {
+            Err("x out of range for width")
+        } else if y >= self.height {
+            Err("y out of range for height")
+        } else {
+            self.get_cell_node(x, y, self.root)
+        }
     }
 
-    fn get_cell_node(&self, row: usize, col: usize, id: NodeId) -> Result<bool, &'static str> {
+    /// reads the cell at `(x, y)` relative to the top left of a node
+    fn get_cell_node(&self, x: usize, y: usize, id: NodeId) -> Result<bool, &'static str> {
         let node = self.get_node(id);
 
-        if let Some(children) = node.children() {
-            let pivot_w = node.rect().width() / 2;
-            let pivot_h = node.rect().height() / 2;
-            if row < pivot_w {
-                if col < pivot_h {
-                    self.get_cell_node(row, col, children.nw())
-                } else {
-                    self.get_cell_node(row, col % pivot_h, children.sw())
-                }
-            } else if col < pivot_h {
-                self.get_cell_node(row % pivot_w, col, children.deref().ne())
-            } else {
-                self.get_cell_node(row % pivot_w, col % pivot_h, children.se())
-            }
+        if node.population() == 0 {
+            Ok(false)
+        } else if let Some(children) = node.children() {
+            let pivot = node.rect().width() / 2;
+            let child = match (x < pivot, y < pivot) {
+                (true, true) => children.nw(),
+                (false, true) => children.deref().ne(),
+                (true, false) => children.sw(),
+                (false, false) => children.se(),
+            };
+            self.get_cell_node(x % pivot, y % pivot, child)
         } else {
-            node.get_cell(row, col)
+            node.get_cell(x, y)
         }
     }
 
-    pub fn toggle_cell(&mut self, row: usize, col: usize) {
-        let mut space: BitSpace = BitSpace::with_capacity(self.width * self.height);
-        self.build_bitspace_from_node(self.root, &mut space);
-
+    pub fn toggle_cell(&mut self, x: usize, y: usize) {
         let val = self
-            .get_cell(row, col)
-            .expect("rol and col to be valid for a node");
-        let idx = self.get_morton(row, col);
-        space.set(idx, !val);
+            .get_cell(x, y)
+            .expect("x and y to be valid for the board");
 
-        self.root = self.node_with_bits(self.width, self.height, &space);
+        self.root = self.set_bits(self.root, &[(x, y)], !val);
+        self.record_history();
+        self.record_ages();
     }
 
     pub fn randomize(&mut self) {
@@ -321,105 +820,264 @@ impl Universe {
     }
 
     pub fn clear(&mut self) {
-        self.root = self.node(self.width, self.height);
+        let size = self.size();
+        self.root = self.node(size, size);
+        self.record_ages();
     }
 
+    /// clears the board and starts counting generations from 0 again, along with
+    /// any history, activity and ages
     pub fn reset(&mut self) {
         self.clear();
+        self.generation = 0;
+        self.clear_history();
+        if let Some(heatmap) = self.heatmap.as_mut() {
+            heatmap.clear();
+        }
+        if let Some(viewport) = self.ages.as_ref().map(CellAges::viewport) {
+            self.set_tracking_ages(Some(viewport));
+        }
     }
 
-    #[rustfmt::skip]
-    pub fn set_flyer(&mut self, row: usize, col: usize) {
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
 
-        let cells = [
-            (row-1, col-1),
-                            (row, col), (row, col+1),
-            (row+1, col-1), (row+1, col)
-        ];
-        self.set_cells(&cells);
+    /// puts the board back to `root`, an earlier root of this universe, as it was
+    /// in `generation`. the envelope keeps everything it has seen, activity and ages
+    /// start over
+    pub fn rewind(&mut self, root: NodeId, generation: usize) -> Result<(), &'static str> {
+        if self.get_node(root).rect().width() != self.size() {
+            return Err("root is the wrong size for the board");
+        }
+        self.root = root;
+        self.generation = generation;
+        self.record_history();
+        if let Some(heatmap) = self.heatmap.as_mut() {
+            heatmap.clear();
+        }
+        if let Some(viewport) = self.ages.as_ref().map(CellAges::viewport) {
+            self.set_tracking_ages(Some(viewport));
+        }
+        Ok(())
+    }
+
+    /// sets the generation count, for boards picked back up from somewhere else
+    pub fn set_generation(&mut self, generation: usize) {
+        self.generation = generation;
+    }
+
+    /// turns history tracking on or off, LifeHistory style. While it's on every cell
+    /// that is alive, after a step or an edit, is added to the envelope. Turning it
+    /// on starts the envelope off as the cells alive now, turning it off forgets it
+    pub fn set_tracking_history(&mut self, tracking: bool) {
+        self.envelope = if tracking { Some(self.root) } else { None };
     }
 
-    #[rustfmt::skip]
-    pub fn set_pulsar(&mut self, r: usize, c: usize) {
+    pub fn is_tracking_history(&self) -> bool {
+        self.envelope.is_some()
+    }
 
-        let cells = [
-                     (r-4, c-6), (r-3, c-6), (r-2, c-6),             (r+2, c-6), (r+3, c-6), (r+4, c-6),
+    /// starts the envelope over from the cells alive now, if history is being tracked
+    pub fn clear_history(&mut self) {
+        if self.envelope.is_some() {
+            self.envelope = Some(self.root);
+        }
+    }
 
-            (r-6, c-4),                          (r-1, c-4),    (r+1, c-4),                           (r+6, c-4),
-            (r-6, c-3),                          (r-1, c-3),    (r+1, c-3),                           (r+6, c-3),
-            (r-6, c-2),                          (r-1, c-2),    (r+1, c-2),                           (r+6, c-2),
-                     (r-4, c-1), (r-3, c-1), (r-2, c-1),             (r+2, c-1), (r+3, c-1), (r+4, c-1),
+    /// adds the live cells to the envelope
+    fn record_history(&mut self) {
+        if let Some(envelope) = self.envelope {
+            let board = Rect::new(0, 0, self.width, self.height);
+            self.envelope = Some(self.paste_node(envelope, self.root, 0, 0, board, PasteMode::Or));
+        }
+    }
 
-                     (r-4, c+1), (r-3, c+1), (r-2, c+1),             (r+2, c+1), (r+3, c+1), (r+4, c+1),
-            (r-6, c+2),                          (r-1, c+2),    (r+1, c+2),                           (r+6, c+2),
-            (r-6, c+3),                          (r-1, c+3),    (r+1, c+3),                           (r+6, c+3),
-            (r-6, c+4),                          (r-1, c+4),    (r+1, c+4),                           (r+6, c+4),
+    /// adds the cells that changed in the last step to the heatmap
+    fn record_activity(&mut self, before: NodeId) {
+        if self.heatmap.is_some() {
+            let changed = self.diff(before, self.root).changed().collect();
+            if let Some(heatmap) = self.heatmap.as_mut() {
+                heatmap.record(changed);
+            }
+        }
+    }
 
-                     (r-4, c+6), (r-3, c+6), (r-2, c+6),             (r+2, c+6), (r+3, c+6), (r+4, c+6),
-        ];
-        self.set_cells(&cells);
+    /// starts or stops counting how often each cell changes, over the last `window`
+    /// generations. changing the window of a running heatmap keeps what it can
+    pub fn set_tracking_activity(&mut self, window: Option<usize>) {
+        self.heatmap = match (self.heatmap.take(), window) {
+            (Some(mut heatmap), Some(window)) => {
+                heatmap.set_window(window);
+                Some(heatmap)
+            }
+            (None, Some(window)) => Some(Heatmap::new(self.width, self.height, window)),
+            (_, None) => None,
+        };
+    }
+
+    /// catches the ages up with the board, births since the last time being born now
+    fn record_ages(&mut self) {
+        if let Some(synced) = self.ages.as_ref().map(|ages| ages.synced) {
+            let diff = self.diff(synced, self.root);
+            let (generation, root) = (self.generation, self.root);
+            if let Some(ages) = self.ages.as_mut() {
+                for &(x, y) in diff.births() {
+                    ages.set_born(x, y, Some(generation));
+                }
+                for &(x, y) in diff.deaths() {
+                    ages.set_born(x, y, None);
+                }
+                ages.set_generation(generation);
+                ages.synced = root;
+            }
+        }
+    }
+
+    /// starts or stops keeping the age of every live cell inside `viewport`, which is
+    /// clipped to the board. cells already alive count as born now. the ages start
+    /// over whenever the board is resized or the viewport changes
+    pub fn set_tracking_ages(&mut self, viewport: Option<Rect>) {
+        let board = Rect::new(0, 0, self.width, self.height);
+        self.ages = viewport
+            .and_then(|viewport| viewport.intersection(&board))
+            .map(|viewport| {
+                CellAges::new(
+                    viewport,
+                    self.live_cells_in(viewport),
+                    self.generation,
+                    self.root,
+                )
+            });
+    }
+
+    /// how long the cells in the tracked viewport have been alive, `None` if they
+    /// aren't being tracked
+    pub fn ages(&self) -> Option<&CellAges> {
+        self.ages.as_ref()
+    }
+
+    /// how often each cell changed recently, `None` if it isn't being tracked
+    pub fn activity(&self) -> Option<&Heatmap> {
+        self.heatmap.as_ref()
+    }
+
+    /// the envelope as a root sized node, to `diff` like `root`. `None` if history
+    /// isn't being tracked
+    pub fn envelope_root(&self) -> Option<NodeId> {
+        self.envelope
+    }
+
+    /// true if the cell at `(x, y)` is in the envelope. always false when history
+    /// isn't being tracked or the cell is off the board
+    pub fn in_envelope(&self, x: usize, y: usize) -> bool {
+        match self.envelope {
+            Some(envelope) if x < self.width && y < self.height => {
+                self.get_cell_node(x, y, envelope) == Ok(true)
+            }
+            _ => false,
+        }
+    }
+
+    /// every cell that has been alive while history was tracked, `None` if it isn't
+    pub fn envelope_cells(&self) -> Option<LiveCells<'_>> {
+        let board = Rect::new(0, 0, self.width, self.height);
+        self.envelope
+            .map(|envelope| LiveCells::new(self, envelope, board))
+    }
+
+    /// the envelope cells inside `rect` as `(x, y)`, in board coordinates
+    pub fn envelope_cells_in(&self, rect: Rect) -> Option<LiveCells<'_>> {
+        self.envelope
+            .map(|envelope| LiveCells::new(self, envelope, rect))
+    }
+
+    /// how many cells have been alive while history was tracked
+    pub fn envelope_population(&self) -> Option<usize> {
+        self.envelope.map(|envelope| self.get_population(envelope))
+    }
+
+    /// the smallest rectangle holding the envelope, for checking a reaction stays
+    /// inside a region. `None` when it's empty or history isn't being tracked
+    pub fn envelope_bounding_box(&self) -> Option<Rect> {
+        let mut bounds = None;
+        self.node_bounds(self.envelope?, 0, 0, &mut bounds);
+
+        bounds.map(|(left, top, right, bottom)| Rect::new(left, top, right - left, bottom - top))
+    }
+
+    /// the envelope inside `rect` as a pattern the size of `rect`, see `copy_region`
+    pub fn copy_envelope(&self, rect: Rect) -> Option<Pattern> {
+        let cells: Vec<_> = self
+            .envelope_cells_in(rect)?
+            .map(|(x, y)| (x - rect.x(), y - rect.y()))
+            .collect();
+
+        Some(Pattern::with_cells(rect.width(), rect.height(), &cells))
+    }
+
+    /// the board as LifeHistory RLE, with the live cells in state 1 and the rest of
+    /// the envelope in state 2. `None` if history isn't being tracked
+    pub fn history_rle(&self) -> Option<String> {
+        let board = Rect::new(0, 0, self.width, self.height);
+        let envelope = self.copy_envelope(board)?;
+        Some(formats::rle::write_history(
+            &self.copy_region(board),
+            &envelope,
+        ))
     }
 
     #[allow(dead_code)]
-    fn live_neighbor_count(&self, row: usize, column: usize) -> usize {
+    fn live_neighbor_count(&self, x: usize, y: usize) -> usize {
         let mut count = 0;
 
-        let north = if row == 0 { self.height - 1 } else { row - 1 };
+        let north = if y == 0 { self.height - 1 } else { y - 1 };
 
-        let south = if row == self.height - 1 { 0 } else { row + 1 };
+        let south = if y == self.height - 1 { 0 } else { y + 1 };
 
-        let west = if column == 0 {
-            self.width - 1
-        } else {
-            column - 1
-        };
+        let west = if x == 0 { self.width - 1 } else { x - 1 };
 
-        let east = if column == self.width - 1 {
-            0
-        } else {
-            column + 1
-        };
+        let east = if x == self.width - 1 { 0 } else { x + 1 };
 
-        count += self.get_cell(north, west).expect("valid cell") as usize;
-        count += self.get_cell(north, column).expect("valid cell") as usize;
-        count += self.get_cell(north, east).expect("valid cell") as usize;
-        count += self.get_cell(row, west).expect("valid cell") as usize;
-        count += self.get_cell(row, east).expect("valid cell") as usize;
-        count += self.get_cell(south, west).expect("valid cell") as usize;
-        count += self.get_cell(south, column).expect("valid cell") as usize;
-        count += self.get_cell(south, east).expect("valid cell") as usize;
+        count += self.get_cell(west, north).expect("valid cell") as usize;
+        count += self.get_cell(x, north).expect("valid cell") as usize;
+        count += self.get_cell(east, north).expect("valid cell") as usize;
+        count += self.get_cell(west, y).expect("valid cell") as usize;
+        count += self.get_cell(east, y).expect("valid cell") as usize;
+        count += self.get_cell(west, south).expect("valid cell") as usize;
+        count += self.get_cell(x, south).expect("valid cell") as usize;
+        count += self.get_cell(east, south).expect("valid cell") as usize;
 
         count
     }
 
-    fn live_neighbor_count_fast(&mut self, row: usize, column: usize, space: &BitSpaceSlice) -> u8 {
+    fn live_neighbor_count_fast(&mut self, x: usize, y: usize, space: &BitSpaceSlice) -> u8 {
         let mut count = 0;
         let ms = &mut self.morton_space;
 
-        let north = row - 1;
-        let south = row + 1;
-        let west = column - 1;
-        let east = column + 1;
+        let north = y - 1;
+        let south = y + 1;
+        let west = x - 1;
+        let east = x + 1;
 
-        count += space[ms.morton2_cache(north, west)] as u8;
-        count += space[ms.morton2_cache(north, column)] as u8;
-        count += space[ms.morton2_cache(north, east)] as u8;
-        count += space[ms.morton2_cache(row, west)] as u8;
-        count += space[ms.morton2_cache(row, east)] as u8;
-        count += space[ms.morton2_cache(south, west)] as u8;
-        count += space[ms.morton2_cache(south, column)] as u8;
-        count += space[ms.morton2_cache(south, east)] as u8;
+        count += space[ms.morton2_cache(west, north)] as u8;
+        count += space[ms.morton2_cache(x, north)] as u8;
+        count += space[ms.morton2_cache(east, north)] as u8;
+        count += space[ms.morton2_cache(west, y)] as u8;
+        count += space[ms.morton2_cache(east, y)] as u8;
+        count += space[ms.morton2_cache(west, south)] as u8;
+        count += space[ms.morton2_cache(x, south)] as u8;
+        count += space[ms.morton2_cache(east, south)] as u8;
 
         count
     }
 
+    /// doubles a node that wraps onto itself, keeping it centered and surrounded by copies of itself
     pub fn expand_and_wrap(&mut self, id: NodeId) -> NodeId {
         let root = self.get_node(id);
-        let (w, h) = (self.width, self.height);
+        let (w, h) = (root.rect().width(), root.rect().height());
         let children = root.children().clone().expect("root to have children");
 
-        let br = self.node(w / 2, h / 2);
-
         let (nw, ne, sw, se) = (
             children.nw(),
             children.deref().ne(),
@@ -427,17 +1085,200 @@ impl Universe {
             children.se(),
         );
 
-        let nw_ex = self.node_with_children(w, h, br, sw, ne, nw);
-        let ne_ex = self.node_with_children(w, h, se, br, ne, nw);
-        let sw_ex = self.node_with_children(w, h, se, sw, br, nw);
-        let se_ex = self.node_with_children(w, h, se, sw, ne, br);
+        // every quadrant of the expanded node sees the root shifted by half its size
+        let shifted = self.node_with_children(w, h, se, sw, ne, nw);
 
-        self.node_with_children(w * 2, h * 2, nw_ex, ne_ex, sw_ex, se_ex)
+        self.node_with_children(w * 2, h * 2, shifted, shifted, shifted, shifted)
+    }
+
+    /// doubles the root and copies the edges of the board onto a `margin` cells wide border
+    /// around it, for boards that don't fill the root. `margin` can't be more than the
+    /// width or height of the board, or half the root
+    fn expand_and_wrap_board(&mut self, id: NodeId, margin: usize) -> NodeId {
+        let (w, h) = (self.width, self.height);
+        let offset = self.get_node(id).rect().width() / 2;
+
+        let strips = [
+            Rect::new(0, 0, w, margin),
+            Rect::new(0, h - margin, w, margin),
+            Rect::new(0, 0, margin, h),
+            Rect::new(w - margin, 0, margin, h),
+        ];
+        let mut edges: Vec<_> = strips
+            .iter()
+            .flat_map(|&strip| LiveCells::new(self, id, strip))
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+
+        // the copies of the board one width or height over in each direction, kept where
+        // they land on the border. `bx` and `by` are counted from the border's outer edge
+        let (left, top) = (offset - margin, offset - margin);
+        let (across, down) = (w + 2 * margin, h + 2 * margin);
+        let shifts = [
+            (0, 0),
+            (1, 0),
+            (2, 0),
+            (0, 1),
+            (2, 1),
+            (0, 2),
+            (1, 2),
+            (2, 2),
+        ];
+        let mut border = Vec::new();
+        for (x, y) in edges {
+            for &(i, j) in &shifts {
+                let bx = (x + i * w + margin).checked_sub(w);
+                let by = (y + j * h + margin).checked_sub(h);
+                if let (Some(bx), Some(by)) = (bx, by) {
+                    if bx < across && by < down {
+                        border.push((left + bx, top + by));
+                    }
+                }
+            }
+        }
+
+        let expanded = self.expand(id);
+        self.set_bits(expanded, &border, true)
+    }
+
+    /// sets every cell in `cells` (relative to the node) to `alive`, sharing untouched subtrees
+    fn set_bits(&mut self, id: NodeId, cells: &[(usize, usize)], alive: bool) -> NodeId {
+        if cells.is_empty() {
+            return id;
+        }
+
+        let node = self.get_node(id);
+        let (w, h) = (node.rect().width(), node.rect().height());
+
+        if let Some(children) = node.children().clone() {
+            let half = w / 2;
+            let mut quadrants: [Vec<(usize, usize)>; 4] = Default::default();
+            for (x, y) in cells.iter().cloned() {
+                let quadrant = (y >= half) as usize * 2 + (x >= half) as usize;
+                quadrants[quadrant].push((x % half, y % half));
+            }
+
+            let nw = self.set_bits(children.nw(), &quadrants[0], alive);
+            let ne = self.set_bits(children.deref().ne(), &quadrants[1], alive);
+            let sw = self.set_bits(children.sw(), &quadrants[2], alive);
+            let se = self.set_bits(children.se(), &quadrants[3], alive);
+
+            self.node_with_children(w, h, nw, ne, sw, se)
+        } else {
+            let mut space = node.space();
+            for (x, y) in cells.iter().cloned() {
+                space.set(morton::morton2(x, y), alive);
+            }
+            self.node_with_bits(w, h, &space)
+        }
+    }
+
+    /// kills every cell of a node outside of the `width` x `height` rectangle at its top left
+    fn mask_node(&mut self, id: NodeId, width: usize, height: usize) -> NodeId {
+        let node = self.get_node(id);
+        let (w, h) = (node.rect().width(), node.rect().height());
+
+        if node.population() == 0 || (width >= w && height >= h) {
+            return id;
+        }
+        if width == 0 || height == 0 {
+            return self.node(w, h);
+        }
+
+        if let Some(children) = node.children().clone() {
+            let half = w / 2;
+            let east = width.saturating_sub(half);
+            let south = height.saturating_sub(half);
+
+            let nw = self.mask_node(children.nw(), width, height);
+            let ne = self.mask_node(children.deref().ne(), east, height);
+            let sw = self.mask_node(children.sw(), width, south);
+            let se = self.mask_node(children.se(), east, south);
+
+            self.node_with_children(w, h, nw, ne, sw, se)
+        } else {
+            let mut space = node.space();
+            for index in 0..space.len() {
+                let (x, y) = morton::unravel_point(index);
+                if x >= width || y >= height {
+                    space.set(index, false);
+                }
+            }
+            self.node_with_bits(w, h, &space)
+        }
+    }
+
+    /// the `size` x `size` square of a node with its top left cell at `(x, y)`,
+    /// relative to the node. parts of the square outside of the node are dead
+    fn window(&mut self, id: NodeId, x: isize, y: isize, size: usize) -> NodeId {
+        let node = self.get_node(id);
+        let n = node.rect().width();
+        let (ni, si) = (n as isize, size as isize);
+
+        if node.population() == 0 || x >= ni || y >= ni || x + si <= 0 || y + si <= 0 {
+            return self.node(size, size);
+        }
+        if x == 0 && y == 0 && size == n {
+            return id;
+        }
+        if size > n {
+            // grow the node around itself until the square fits inside it
+            let expanded = self.expand(id);
+            return self.window(expanded, x + ni / 2, y + ni / 2, size);
+        }
+        if x == ni / 4 && y == ni / 4 && size == n / 2 {
+            return self.centered_subnode(id);
+        }
+
+        let half = ni / 2;
+        let quadrant = |pos: isize| {
+            if pos >= half {
+                Some(1)
+            } else if pos + si <= half {
+                Some(0)
+            } else {
+                None
+            }
+        };
+
+        if let (Some(children), Some(qx), Some(qy)) = (node.children(), quadrant(x), quadrant(y)) {
+            // the square sits inside a single child
+            let child = match (qx, qy) {
+                (0, 0) => children.nw(),
+                (1, 0) => children.deref().ne(),
+                (0, _) => children.sw(),
+                _ => children.se(),
+            };
+            self.window(child, x - qx * half, y - qy * half, size)
+        } else if size > Self::MIN_NODE_WIDTH {
+            let h = si / 2;
+            let nw = self.window(id, x, y, size / 2);
+            let ne = self.window(id, x + h, y, size / 2);
+            let sw = self.window(id, x, y + h, size / 2);
+            let se = self.window(id, x + h, y + h, size / 2);
+
+            self.node_with_children(size, size, nw, ne, sw, se)
+        } else {
+            let mut space: BitSpace = BitSpace::repeat(false, size * size);
+            for index in 0..(size * size) {
+                let (cx, cy) = morton::unravel_point(index);
+                let (px, py) = (x + cx as isize, y + cy as isize);
+                if px >= 0 && py >= 0 && px < ni && py < ni {
+                    let alive = self
+                        .get_cell_node(px as usize, py as usize, id)
+                        .expect("x and y to be inside the node");
+                    space.set(index, alive);
+                }
+            }
+            self.node_with_bits(size, size, &space)
+        }
     }
 
     pub fn expand(&mut self, id: NodeId) -> NodeId {
         let root = self.get_node(id).clone();
-        let br = self.node(self.width / 2, self.height / 2);
+        let (w, h) = (root.rect().width(), root.rect().height());
+        let br = self.node(w / 2, h / 2);
 
         let children = root.children().clone().expect("root to have children");
         let (nw, ne, sw, se) = (
@@ -446,7 +1287,6 @@ impl Universe {
             children.sw(),
             children.se(),
         );
-        let (w, h) = (self.width, self.height);
 
         let nw_ex = self.node_with_children(w, h, br, br, br, nw);
         let ne_ex = self.node_with_children(w, h, br, br, ne, br);
@@ -457,10 +1297,42 @@ impl Universe {
     }
 
     pub fn step(&mut self) {
+        self.advance(0);
+    }
+
+    /// steps the board `generations` times, jumping ahead as many generations at once
+    /// as the board allows: up to half the root on boards that fill it, and otherwise
+    /// up to the board's width and height as well. a jump only wraps the board once
+    /// and leaves out the generations in between, so the history, heatmap and ages
+    /// only see the boards it lands on
+    pub fn step_by(&mut self, generations: usize) {
+        let mut limit = self.size() / 2;
+        if !self.fills_root() {
+            limit = limit.min(self.width).min(self.height);
+        }
+        let mut left = generations;
+        while left > 0 {
+            let jump = left.min(limit);
+            // the largest power of two that fits
+            let power = (usize::BITS - 1 - jump.leading_zeros()) as usize;
+            self.advance(power);
+            left -= 1 << power;
+        }
+    }
+
+    /// steps the board `2^power` generations at once, at most half the root and, on
+    /// boards that don't fill it, at most their width and height
+    fn advance(&mut self, power: usize) {
+        let before = self.root;
         let mut root_level = self.get_node(self.root).level();
         let mut root_id = self.root;
 
-        root_id = self.expand_and_wrap(root_id);
+        let fills_root = self.fills_root();
+        root_id = if fills_root {
+            self.expand_and_wrap(root_id)
+        } else {
+            self.expand_and_wrap_board(root_id, 1 << power)
+        };
 
         // do extra expansions to make sure we have enough space
         let mut exp = 0;
@@ -471,14 +1343,23 @@ impl Universe {
         }
 
         // step node and shrink one size down
-        root_id = self.step_node(root_id);
+        root_id = self.jump_node(root_id, power);
 
         // unwrap any extra expansions
         for _ in 0..exp {
             root_id = self.centered_subnode(root_id);
         }
 
+        // drop anything that grew past the edges of the board
+        if !fills_root {
+            root_id = self.mask_node(root_id, self.width, self.height);
+        }
+
         self.root = root_id;
+        self.generation += 1 << power;
+        self.record_history();
+        self.record_ages();
+        self.record_activity(before);
     }
 
     pub fn step_node(&mut self, id: NodeId) -> NodeId {
@@ -539,6 +1420,118 @@ impl Universe {
         next
     }
 
+    /// the centered half of a node `2^power` generations later. `power` can be as
+    /// large as the node's level, a node that big jumps by stepping the nine nodes
+    /// between its children half way and the four they make up the rest of the way
+    pub fn jump_node(&mut self, id: NodeId, power: usize) -> NodeId {
+        if power == 0 {
+            return self.step_node(id);
+        }
+        if let Some(next) = self.jump_node_map.get(&(id, power)) {
+            return *next;
+        }
+
+        let node = self.get_node(id);
+
+        let population = node.population();
+        let level = node.level();
+        let (width, height) = (node.rect().width(), node.rect().height());
+        let next = if population == 0 {
+            node.children().clone().expect("node to have children").nw()
+        } else if population < 3 {
+            self.node(width / 2, height / 2)
+        } else if level == 2 {
+            self.slow_jump(id, 1 << power)
+        } else {
+            let (w, h) = (width / 2, height / 2);
+            let ch = node.children().clone().expect("node to have children");
+
+            let (nine, power) = if power == level {
+                let between = [
+                    ch.nw(),
+                    self.between_horizontal(ch.nw(), ch.deref().ne()),
+                    ch.deref().ne(),
+                    self.between_vertical(ch.nw(), ch.sw()),
+                    self.centered_subnode(id),
+                    self.between_vertical(ch.deref().ne(), ch.se()),
+                    ch.sw(),
+                    self.between_horizontal(ch.sw(), ch.se()),
+                    ch.se(),
+                ];
+                let mut nine = [id; 9];
+                for (stepped, node) in nine.iter_mut().zip(between.iter()) {
+                    *stepped = self.jump_node(*node, power - 1);
+                }
+                (nine, power - 1)
+            } else {
+                let nine = [
+                    self.centered_subnode(ch.nw()),
+                    self.centered_horizontal(ch.nw(), ch.deref().ne()),
+                    self.centered_subnode(ch.deref().ne()),
+                    self.centered_vertical(ch.nw(), ch.sw()),
+                    self.centered_sub_subnode(id),
+                    self.centered_vertical(ch.deref().ne(), ch.se()),
+                    self.centered_subnode(ch.sw()),
+                    self.centered_horizontal(ch.sw(), ch.se()),
+                    self.centered_subnode(ch.se()),
+                ];
+                (nine, power)
+            };
+            let [n00, n01, n02, n10, n11, n12, n20, n21, n22] = nine;
+
+            let nw_pre = self.node_with_children(w, h, n00, n01, n10, n11);
+            let ne_pre = self.node_with_children(w, h, n01, n02, n11, n12);
+            let sw_pre = self.node_with_children(w, h, n10, n11, n20, n21);
+            let se_pre = self.node_with_children(w, h, n11, n12, n21, n22);
+
+            let nw = self.jump_node(nw_pre, power);
+            let ne = self.jump_node(ne_pre, power);
+            let sw = self.jump_node(sw_pre, power);
+            let se = self.jump_node(se_pre, power);
+
+            self.node_with_children(w, h, nw, ne, sw, se)
+        };
+
+        self.jump_node_map.insert((id, power), next);
+
+        next
+    }
+
+    /// the centered half of a 16x16 node `generations` later, at most 4
+    fn slow_jump(&mut self, id: NodeId, generations: usize) -> NodeId {
+        let node = self.get_node(id);
+        let (w, h) = (node.rect().width(), node.rect().height());
+        let space = self.build_bitspace_fast(id);
+
+        let mut cells: Vec<bool> = (0..w * h)
+            .map(|index| space[morton::morton2(index % w, index / w)])
+            .collect();
+        for _ in 0..generations {
+            cells = (0..w * h)
+                .map(|index| {
+                    let (x, y) = (index % w, index / w);
+                    let mut count = 0;
+                    for ny in y.saturating_sub(1)..(y + 2).min(h) {
+                        for nx in x.saturating_sub(1)..(x + 2).min(w) {
+                            count += ((nx, ny) != (x, y) && cells[ny * w + nx]) as u8;
+                        }
+                    }
+                    count == 3 || (count == 2 && cells[index])
+                })
+                .collect();
+        }
+
+        let (w2, h2) = (w / 2, h / 2);
+        let mut next: BitSpace = BitSpace::repeat(false, w2 * h2);
+        for y in 0..h2 {
+            for x in 0..w2 {
+                next.set(morton::morton2(x, y), cells[(y + h2 / 2) * w + x + w2 / 2]);
+            }
+        }
+
+        self.node_with_bits(w2, h2, &next)
+    }
+
     pub fn slow_sim(&mut self, id: NodeId) -> NodeId {
         // let _timer = Timer::new("Universe::slow_sim");
         let node = self.get_node(id);
@@ -596,6 +1589,40 @@ impl Universe {
         self.node_with_children(w, h, nw, ne, sw, se)
     }
 
+    /// the node as big as `w` and `e` straddling the edge between them
+    fn between_horizontal(&mut self, w: NodeId, e: NodeId) -> NodeId {
+        let node = self.get_node(w);
+        let (width, height) = (node.rect().width(), node.rect().height());
+        let w_ch = self.get_children(w);
+        let e_ch = self.get_children(e);
+
+        self.node_with_children(
+            width,
+            height,
+            w_ch.deref().ne(),
+            e_ch.nw(),
+            w_ch.se(),
+            e_ch.sw(),
+        )
+    }
+
+    /// the node as big as `n` and `s` straddling the edge between them
+    fn between_vertical(&mut self, n: NodeId, s: NodeId) -> NodeId {
+        let node = self.get_node(n);
+        let (width, height) = (node.rect().width(), node.rect().height());
+        let n_ch = self.get_children(n);
+        let s_ch = self.get_children(s);
+
+        self.node_with_children(
+            width,
+            height,
+            n_ch.sw(),
+            n_ch.se(),
+            s_ch.nw(),
+            s_ch.deref().ne(),
+        )
+    }
+
     fn centered_horizontal(&mut self, w: NodeId, e: NodeId) -> NodeId {
         let w_node = self.get_node(w);
         let e_node = self.get_node(e);
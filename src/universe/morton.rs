@@ -3,6 +3,10 @@
 use std::collections::HashMap;
 
 
+/// Cache of morton indices for a `width` x `height` space.
+///
+/// Like everywhere else `x` is the column and `y` the row, the cache is laid
+/// out row by row.
 #[derive(Debug, Clone)]
 pub struct MortonSpace {
     width: usize,
@@ -20,7 +24,7 @@ impl MortonSpace {
     }
 
     pub fn morton2_cache(&mut self, x: usize, y: usize) -> usize {
-        let index = x * self.width + y;
+        let index = y * self.width + x;
         self.table[index].unwrap_or_else(|| {
             let r = interleave_with_zeros(x) | (interleave_with_zeros(y) << 1);
             self.table[index] = Some(r);
@@ -29,28 +33,23 @@ impl MortonSpace {
     }
 
     pub fn morton2(&self, x: usize, y: usize) -> usize {
-        self.table[x * self.width + y].unwrap_or_else(|| interleave_with_zeros(x) | (interleave_with_zeros(y) << 1))
+        self.table[y * self.width + x].unwrap_or_else(|| interleave_with_zeros(x) | (interleave_with_zeros(y) << 1))
     }
 
 
     pub fn valid(&self, x: usize, y: usize) -> bool {
-        self.table[x * self.width + y].is_some()
+        x < self.width && y < self.height && self.table[y * self.width + x].is_some()
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct MortonCache {
     pub map: HashMap<(usize, usize), MortonSpace>
 }
 
-impl Default for MortonCache {
-    fn default() -> Self {
-        MortonCache {
-            map: HashMap::new()
-        }
-    }
-}
-
+/// Z-order index of column `x`, row `y`: the bits of `x` and `y` interleaved,
+/// `x` taking the low bit. Splitting the index in four quarters gives the
+/// nw, ne, sw and se quadrants in that order.
 pub fn morton2(x: usize, y: usize) -> usize {
     interleave_with_zeros(x) | (interleave_with_zeros(y) << 1)
 }
@@ -2,6 +2,7 @@
 
 use bitvec::prelude as bv;
 
+use super::morton;
 use super::rect::Rectangle;
 
 use std::fmt::Debug;
@@ -140,15 +141,15 @@ impl Node {
         self.level
     }
 
-    pub fn get_cell(&self, row: usize, col: usize) -> Result<bool, &'static str> {
-        if self.has_space() {
-            let space = self.space();
-            if row >= self.rect.width() {
-                Err("row out of range for width")
-            } else if col >= self.rect.height() {
-                Err("col out of range for height")
+    /// reads the cell at column `x`, row `y` of a leaf, leaves store their cells in morton order
+    pub fn get_cell(&self, x: usize, y: usize) -> Result<bool, &'static str> {
+        if let Some(space) = &self.space {
+            if x >= self.rect.width() {
+                Err("x out of range for width")
+            } else if y >= self.rect.height() {
+                Err("y out of range for height")
             } else {
-                Ok(space[row * self.rect.width() + col])
+                Ok(space[morton::morton2(x, y)])
             }
         } else {
             Err("Node doesn't have a bit space! ask a child.")
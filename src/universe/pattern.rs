@@ -0,0 +1,210 @@
+/// A standalone rectangle of cells that isn't attached to any `Universe`.
+///
+/// Cells are stored as `(x, y)` pairs relative to the top left corner, sorted
+/// row by row, so two patterns with the same cells and size compare equal.
+///
+/// Patterns aren't kept as quadtree nodes. Nodes sit on a power of two grid and a
+/// pattern can be pasted at any offset, so a node built for it (or for one of its
+/// transforms) would almost never line up with the board's nodes and would have to
+/// be split back into cells anyway. Pasting builds the pattern's nodes in place,
+/// through the universe's node map, so what ends up on the board is shared there.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Pattern {
+    width: usize,
+    height: usize,
+    cells: Vec<(usize, usize)>,
+}
+
+impl Pattern {
+    /// an empty `width` x `height` pattern
+    pub fn new(width: usize, height: usize) -> Self {
+        Pattern {
+            width,
+            height,
+            cells: vec![],
+        }
+    }
+
+    /// a `width` x `height` pattern, cells outside of it are dropped
+    pub fn with_cells(width: usize, height: usize, cells: &[(usize, usize)]) -> Self {
+        let mut cells: Vec<_> = cells
+            .iter()
+            .cloned()
+            .filter(|&(x, y)| x < width && y < height)
+            .collect();
+        cells.sort_unstable_by_key(|&(x, y)| (y, x));
+        cells.dedup();
+
+        Pattern {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// a pattern just big enough to hold `cells`, measured from `(0, 0)`
+    pub fn from_cells(cells: &[(usize, usize)]) -> Self {
+        let width = cells.iter().map(|&(x, _)| x + 1).max().unwrap_or(0);
+        let height = cells.iter().map(|&(_, y)| y + 1).max().unwrap_or(0);
+        Pattern::with_cells(width, height, cells)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// the live cells, sorted row by row
+    pub fn cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get_cell(&self, x: usize, y: usize) -> bool {
+        self.cells
+            .binary_search_by_key(&(y, x), |&(cx, cy)| (cy, cx))
+            .is_ok()
+    }
+
+    /// the same pattern with any empty rows and columns around it cut off
+    pub fn trimmed(&self) -> Pattern {
+        let left = self.cells.iter().map(|&(x, _)| x).min().unwrap_or(0);
+        let top = self.cells.first().map(|&(_, y)| y).unwrap_or(0);
+        let cells: Vec<_> = self
+            .cells
+            .iter()
+            .map(|&(x, y)| (x - left, y - top))
+            .collect();
+
+        Pattern::from_cells(&cells)
+    }
+
+    /// the pattern rotated, flipped or transposed
+    pub fn transform(&self, transform: Transform) -> Pattern {
+        let (width, height) = transform.dimensions(self.width, self.height);
+        let cells: Vec<_> = self
+            .cells
+            .iter()
+            .map(|&(x, y)| transform.apply(x, y, self.width, self.height))
+            .collect();
+
+        Pattern::with_cells(width, height, &cells)
+    }
+
+    /// the trimmed pattern in whichever of its eight orientations sorts first,
+    /// so every rotation and reflection of a pattern has the same canonical form
+    pub fn canonical(&self) -> Pattern {
+        let trimmed = self.trimmed();
+        Transform::ALL
+            .iter()
+            .map(|&transform| trimmed.transform(transform))
+            .min_by(|a, b| (a.width, a.height, &a.cells).cmp(&(b.width, b.height, &b.cells)))
+            .expect("there to be transforms")
+    }
+}
+
+/// The eight symmetries of a square, the dihedral group D4.
+///
+/// Rotations are clockwise, with `y` growing down the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Transform {
+    #[default]
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// mirrored left to right
+    FlipHorizontal,
+    /// mirrored top to bottom
+    FlipVertical,
+    /// mirrored along the top left to bottom right diagonal
+    Transpose,
+    /// mirrored along the top right to bottom left diagonal
+    AntiTranspose,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::Transpose,
+        Transform::AntiTranspose,
+    ];
+
+    /// true for the transforms that swap width and height
+    pub fn swaps_axes(self) -> bool {
+        matches!(
+            self,
+            Transform::Rotate90
+                | Transform::Rotate270
+                | Transform::Transpose
+                | Transform::AntiTranspose
+        )
+    }
+
+    /// the size of a `width` x `height` rectangle after the transform
+    pub fn dimensions(self, width: usize, height: usize) -> (usize, usize) {
+        if self.swaps_axes() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// where the cell `(x, y)` of a `width` x `height` rectangle ends up
+    pub fn apply(self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+        let (right, bottom) = (width - 1 - x, height - 1 - y);
+        match self {
+            Transform::Identity => (x, y),
+            Transform::Rotate90 => (bottom, x),
+            Transform::Rotate180 => (right, bottom),
+            Transform::Rotate270 => (y, right),
+            Transform::FlipHorizontal => (right, y),
+            Transform::FlipVertical => (x, bottom),
+            Transform::Transpose => (y, x),
+            Transform::AntiTranspose => (bottom, right),
+        }
+    }
+}
+
+/// How a pasted pattern is combined with the cells already under it.
+///
+/// Cells outside of the pattern's rectangle are never touched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PasteMode {
+    /// live cells of either stay alive
+    #[default]
+    Or,
+    /// the pattern replaces everything under it
+    Copy,
+    /// cells alive in exactly one of them stay alive
+    Xor,
+    /// only cells alive in both stay alive
+    And,
+}
+
+impl PasteMode {
+    /// the state of a cell inside the pasted rectangle
+    pub fn apply(self, old: bool, new: bool) -> bool {
+        match self {
+            PasteMode::Or => old || new,
+            PasteMode::Copy => new,
+            PasteMode::Xor => old != new,
+            PasteMode::And => old && new,
+        }
+    }
+}
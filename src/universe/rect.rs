@@ -19,3 +19,104 @@ impl Rectangle {
         self.height
     }
 }
+
+/// A rectangle of cells on the board, `(x, y)` being its top left cell
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Rect {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl Rect {
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn x(&self) -> usize {
+        self.x
+    }
+
+    pub fn y(&self) -> usize {
+        self.y
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// the column just past the east edge
+    pub fn right(&self) -> usize {
+        self.x + self.width
+    }
+
+    /// the row just past the south edge
+    pub fn bottom(&self) -> usize {
+        self.y + self.height
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    /// the part of both rectangles that overlaps, if any
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let (x, y) = (self.x.max(other.x), self.y.max(other.y));
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+
+        if x < right && y < bottom {
+            Some(Rect::new(x, y, right - x, bottom - y))
+        } else {
+            None
+        }
+    }
+}
+
+/// Merges cells into as few rectangles as is easy, first along each row and then
+/// down runs that line up from one row to the next. Sorts `cells` row by row.
+pub fn cover(cells: &mut [(usize, usize)]) -> Vec<Rect> {
+    cells.sort_unstable_by_key(|&(x, y)| (y, x));
+
+    let mut runs: Vec<Rect> = Vec::new();
+    for &(x, y) in cells.iter() {
+        match runs.last_mut() {
+            Some(run) if run.y == y && run.right() == x => run.width += 1,
+            _ => runs.push(Rect::new(x, y, 1, 1)),
+        }
+    }
+
+    // runs come a row at a time, so a rectangle can only grow if it ended on the row above
+    let mut rects: Vec<Rect> = Vec::new();
+    let mut open: Vec<usize> = Vec::new();
+    for run in runs {
+        let above = open.iter().position(|&i| {
+            let rect = &rects[i];
+            rect.x == run.x && rect.width == run.width && rect.bottom() == run.y
+        });
+        match above {
+            Some(position) => {
+                let i = open.remove(position);
+                rects[i].height += 1;
+                open.push(i);
+            }
+            None => {
+                open.push(rects.len());
+                rects.push(run);
+            }
+        }
+        // anything that didn't carry on into this row is finished
+        open.retain(|&i| rects[i].bottom() >= run.y);
+    }
+    rects
+}
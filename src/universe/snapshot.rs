@@ -0,0 +1,188 @@
+// a compact copy of a universe's cells, for saving or sending somewhere
+
+use std::collections::HashMap;
+
+use super::node::{BitSpace, NodeId};
+use super::Universe;
+
+/// the only rule the universe runs, in B/S notation
+pub const RULE: &str = "B3/S23";
+
+/// One node of a snapshot
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SnapshotNode {
+    /// a 4x4 leaf, its cells in morton order with the first in the highest bit
+    Leaf(u16),
+    /// the `nw`, `ne`, `sw` and `se` children, as indices of earlier nodes
+    Branch([u32; 4]),
+}
+
+/// The cells of a universe as the nodes reachable from its root, each stored
+/// once, plus what's needed to run it again. the memo caches aren't kept, they
+/// fill back up as the universe is stepped
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    rule: String,
+    /// the board, in Golly's notation: `T<width>,<height>` for a torus
+    topology: String,
+    generation: usize,
+    /// children always come before their parents, the root is last
+    nodes: Vec<SnapshotNode>,
+}
+
+impl Snapshot {
+    pub fn rule(&self) -> &str {
+        &self.rule
+    }
+
+    pub fn topology(&self) -> &str {
+        &self.topology
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn nodes(&self) -> &[SnapshotNode] {
+        &self.nodes
+    }
+
+    /// the board width and height from the topology
+    fn board(&self) -> Result<(usize, usize), &'static str> {
+        let size = self
+            .topology
+            .strip_prefix('T')
+            .ok_or("only torus topologies are supported")?;
+        let (width, height) = size.split_once(',').ok_or("topology is missing a height")?;
+        let width = width.parse().map_err(|_| "topology width isn't a number")?;
+        let height = height
+            .parse()
+            .map_err(|_| "topology height isn't a number")?;
+        Ok((width, height))
+    }
+}
+
+impl Universe {
+    /// a snapshot of the board, see `Snapshot`
+    pub fn snapshot(&self) -> Snapshot {
+        let mut nodes = vec![];
+        let mut indices = HashMap::new();
+        self.snapshot_node(self.root, &mut nodes, &mut indices);
+
+        Snapshot {
+            rule: RULE.to_string(),
+            topology: format!("T{},{}", self.width, self.height),
+            generation: self.generation,
+            nodes,
+        }
+    }
+
+    /// adds `id` and everything under it to `nodes`, returning its index
+    fn snapshot_node(
+        &self,
+        id: NodeId,
+        nodes: &mut Vec<SnapshotNode>,
+        indices: &mut HashMap<NodeId, u32>,
+    ) -> u32 {
+        if let Some(&index) = indices.get(&id) {
+            return index;
+        }
+
+        let node = self.get_node(id);
+        let snapshot_node = match node.children().as_deref() {
+            Some(children) => SnapshotNode::Branch([
+                self.snapshot_node(children.nw(), nodes, indices),
+                self.snapshot_node(children.ne(), nodes, indices),
+                self.snapshot_node(children.sw(), nodes, indices),
+                self.snapshot_node(children.se(), nodes, indices),
+            ]),
+            None => SnapshotNode::Leaf(
+                node.space()
+                    .iter()
+                    .fold(0, |bits, bit| bits << 1 | *bit as u16),
+            ),
+        };
+
+        let index = nodes.len() as u32;
+        nodes.push(snapshot_node);
+        indices.insert(id, index);
+        index
+    }
+
+    /// rebuilds a universe from a snapshot
+    pub fn from_snapshot(snapshot: &Snapshot) -> Result<Universe, &'static str> {
+        if snapshot.rule != RULE {
+            return Err("only B3/S23 is supported");
+        }
+        let (width, height) = snapshot.board()?;
+        if width == 0 || height == 0 {
+            return Err("board has no cells");
+        }
+
+        let mut universe = Universe::new(width, height);
+        // the id and side of each node so far
+        let mut built: Vec<(NodeId, usize)> = Vec::with_capacity(snapshot.nodes.len());
+        for node in snapshot.nodes.iter() {
+            let next = match node {
+                SnapshotNode::Leaf(bits) => {
+                    let side = Universe::MIN_NODE_WIDTH;
+                    let space: BitSpace = (0..side * side)
+                        .rev()
+                        .map(|bit| bits >> bit & 1 == 1)
+                        .collect();
+                    (universe.node_with_bits(side, side, &space), side)
+                }
+                SnapshotNode::Branch(children) => {
+                    let mut ids = [NodeId::new(0); 4];
+                    let mut sides = [0; 4];
+                    for (quadrant, &child) in children.iter().enumerate() {
+                        let &(id, side) = built
+                            .get(child as usize)
+                            .ok_or("node refers to a node after it")?;
+                        ids[quadrant] = id;
+                        sides[quadrant] = side;
+                    }
+                    if sides.iter().any(|&side| side != sides[0]) {
+                        return Err("node has children of different sizes");
+                    }
+                    let side = sides[0]
+                        .checked_mul(2)
+                        .filter(|&side| side <= universe.size())
+                        .ok_or("node is bigger than the board")?;
+                    let [nw, ne, sw, se] = ids;
+                    (
+                        universe.node_with_children(side, side, nw, ne, sw, se),
+                        side,
+                    )
+                }
+            };
+            built.push(next);
+        }
+
+        let &(root, side) = built.last().ok_or("snapshot has no nodes")?;
+        if side != universe.size() {
+            return Err("root is the wrong size for the board");
+        }
+        // anything outside of the board has to stay dead
+        universe.root = universe.mask_node(root, width, height);
+        universe.generation = snapshot.generation;
+        Ok(universe)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Universe {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.snapshot().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Universe {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let snapshot = Snapshot::deserialize(deserializer)?;
+        Universe::from_snapshot(&snapshot).map_err(serde::de::Error::custom)
+    }
+}
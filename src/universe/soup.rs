@@ -0,0 +1,199 @@
+// seeded random soups, with the symmetries apgsearch uses
+
+use std::fmt;
+use std::str::FromStr;
+
+use rand::{Rng, SeedableRng};
+
+use super::pattern::{Pattern, Transform};
+
+/// The soup symmetries from apgsearch.
+///
+/// The number after the group says where the centre of symmetry sits: `1` on
+/// a cell, `2` on the edge between two cells and `4` on the corner between four.
+/// `+` mirrors across the horizontal and vertical axes, `x` across the diagonals.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Symmetry {
+    #[default]
+    C1,
+    C2_1,
+    C2_2,
+    C2_4,
+    C4_1,
+    C4_4,
+    D2_Plus1,
+    D2_Plus2,
+    D2_X,
+    D4_Plus1,
+    D4_Plus2,
+    D4_Plus4,
+    D4_X1,
+    D4_X4,
+    D8_1,
+    D8_4,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 16] = [
+        Symmetry::C1,
+        Symmetry::C2_1,
+        Symmetry::C2_2,
+        Symmetry::C2_4,
+        Symmetry::C4_1,
+        Symmetry::C4_4,
+        Symmetry::D2_Plus1,
+        Symmetry::D2_Plus2,
+        Symmetry::D2_X,
+        Symmetry::D4_Plus1,
+        Symmetry::D4_Plus2,
+        Symmetry::D4_Plus4,
+        Symmetry::D4_X1,
+        Symmetry::D4_X4,
+        Symmetry::D8_1,
+        Symmetry::D8_4,
+    ];
+
+    /// the name apgsearch and Catagolue use
+    pub fn name(self) -> &'static str {
+        match self {
+            Symmetry::C1 => "C1",
+            Symmetry::C2_1 => "C2_1",
+            Symmetry::C2_2 => "C2_2",
+            Symmetry::C2_4 => "C2_4",
+            Symmetry::C4_1 => "C4_1",
+            Symmetry::C4_4 => "C4_4",
+            Symmetry::D2_Plus1 => "D2_+1",
+            Symmetry::D2_Plus2 => "D2_+2",
+            Symmetry::D2_X => "D2_x",
+            Symmetry::D4_Plus1 => "D4_+1",
+            Symmetry::D4_Plus2 => "D4_+2",
+            Symmetry::D4_Plus4 => "D4_+4",
+            Symmetry::D4_X1 => "D4_x1",
+            Symmetry::D4_X4 => "D4_x4",
+            Symmetry::D8_1 => "D8_1",
+            Symmetry::D8_4 => "D8_4",
+        }
+    }
+
+    /// the transforms that leave a soup unchanged
+    pub fn transforms(self) -> &'static [Transform] {
+        use Transform::*;
+        match self {
+            Symmetry::C1 => &[Identity],
+            Symmetry::C2_1 | Symmetry::C2_2 | Symmetry::C2_4 => &[Identity, Rotate180],
+            Symmetry::C4_1 | Symmetry::C4_4 => &[Identity, Rotate90, Rotate180, Rotate270],
+            Symmetry::D2_Plus1 | Symmetry::D2_Plus2 => &[Identity, FlipHorizontal],
+            Symmetry::D2_X => &[Identity, Transpose],
+            Symmetry::D4_Plus1 | Symmetry::D4_Plus2 | Symmetry::D4_Plus4 => {
+                &[Identity, FlipHorizontal, FlipVertical, Rotate180]
+            }
+            Symmetry::D4_X1 | Symmetry::D4_X4 => &[Identity, Transpose, AntiTranspose, Rotate180],
+            Symmetry::D8_1 | Symmetry::D8_4 => &Transform::ALL,
+        }
+    }
+
+    /// whether the width and height have to be odd (`Some(true)`) or even (`Some(false)`)
+    /// to put the centre of symmetry in the right place
+    fn parity(self) -> (Option<bool>, Option<bool>) {
+        match self {
+            Symmetry::C1 | Symmetry::D2_X => (None, None),
+            Symmetry::C2_1
+            | Symmetry::C4_1
+            | Symmetry::D4_Plus1
+            | Symmetry::D4_X1
+            | Symmetry::D8_1 => (Some(true), Some(true)),
+            Symmetry::C2_2 | Symmetry::D4_Plus2 => (Some(false), Some(true)),
+            Symmetry::C2_4
+            | Symmetry::C4_4
+            | Symmetry::D4_Plus4
+            | Symmetry::D4_X4
+            | Symmetry::D8_4 => (Some(false), Some(false)),
+            Symmetry::D2_Plus1 => (Some(true), None),
+            Symmetry::D2_Plus2 => (Some(false), None),
+        }
+    }
+
+    /// true when the soup has to be square
+    pub fn is_square(self) -> bool {
+        self.transforms()
+            .iter()
+            .any(|transform| transform.swaps_axes())
+    }
+
+    /// the biggest soup with this symmetry that fits in `width` x `height`
+    pub fn fit(self, width: usize, height: usize) -> (usize, usize) {
+        let (mut width, mut height) = (width, height);
+        if self.is_square() {
+            width = width.min(height);
+            height = width;
+        }
+
+        let fix = |n: usize, odd: Option<bool>| match odd {
+            Some(odd) if (n % 2 == 1) != odd => n.saturating_sub(1),
+            _ => n,
+        };
+        let (odd_width, odd_height) = self.parity();
+        (fix(width, odd_width), fix(height, odd_height))
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Symmetry::ALL
+            .iter()
+            .cloned()
+            .find(|symmetry| symmetry.name() == s)
+            .ok_or("unknown symmetry")
+    }
+}
+
+/// a generator that gives the same numbers for the same seed on every platform
+pub fn rng_from_seed(seed: &str) -> rand_chacha::ChaCha8Rng {
+    // FNV-1a, so the seed doesn't depend on the platform's hasher
+    let hash = seed.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    rand_chacha::ChaCha8Rng::seed_from_u64(hash)
+}
+
+/// a random soup of about `width` x `height` (see `Symmetry::fit`) where each
+/// cell is alive with chance `density`, a density that isn't a finite number
+/// giving an empty soup. the same seed always gives the same soup
+pub fn soup(seed: &str, density: f64, width: usize, height: usize, symmetry: Symmetry) -> Pattern {
+    let (width, height) = symmetry.fit(width, height);
+    let density = if density.is_finite() {
+        density.clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let mut rng = rng_from_seed(seed);
+
+    let bits: Vec<bool> = (0..width * height).map(|_| rng.gen_bool(density)).collect();
+
+    // every cell copies the first cell of its orbit under the symmetry
+    let mut cells = vec![];
+    for y in 0..height {
+        for x in 0..width {
+            let (rx, ry) = symmetry
+                .transforms()
+                .iter()
+                .map(|transform| transform.apply(x, y, width, height))
+                .min_by_key(|&(x, y)| (y, x))
+                .expect("there to be transforms");
+            if bits[ry * width + rx] {
+                cells.push((x, y));
+            }
+        }
+    }
+
+    Pattern::with_cells(width, height, &cells)
+}
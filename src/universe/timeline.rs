@@ -0,0 +1,217 @@
+// past boards kept every few generations, to scrub back and forth through
+
+use super::node::NodeId;
+use super::Universe;
+
+/// how many generations apart checkpoints start out
+pub const DEFAULT_INTERVAL: usize = 10;
+/// how many checkpoints are kept before they start being thinned out. it counts
+/// checkpoints, it doesn't bound memory, see `Timeline`
+pub const DEFAULT_LIMIT: usize = 1000;
+
+/// A board kept by a `Timeline`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub generation: usize,
+    pub root: NodeId,
+    /// whether the board was edited in this generation rather than stepped into it,
+    /// in which case it can't be simulated from the checkpoints before it
+    pub edited: bool,
+}
+
+/// The root of the board every `interval` generations.
+///
+/// Roots are node ids of the universe they were recorded from. A generation between
+/// checkpoints is reached by simulating forward from the one before it.
+///
+/// Once there are more than `limit` checkpoints every other one is dropped and the
+/// interval doubles, so the whole run stays reachable, just more slowly.
+///
+/// `limit` is a number of checkpoints, not an amount of memory. The universe never
+/// frees a node, so its memory keeps growing as the board is stepped whether or not
+/// the roots are kept here, and dropping a checkpoint frees nothing.
+///
+/// Editing the board in a past generation branches off from there: the checkpoints
+/// after it are forgotten and the edited board is kept as a checkpoint of its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeline {
+    interval: usize,
+    limit: usize,
+    /// in generation order
+    checkpoints: Vec<Checkpoint>,
+    /// the generation and root last recorded, to tell steps apart from edits
+    head: Option<(usize, NodeId)>,
+    /// the furthest generation reached on this branch
+    latest: usize,
+    /// the board size the roots belong to
+    size: (usize, usize),
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Timeline::new(DEFAULT_INTERVAL, DEFAULT_LIMIT)
+    }
+}
+
+impl Timeline {
+    /// an empty timeline keeping a checkpoint every `interval` generations, up to
+    /// `limit` of them
+    pub fn new(interval: usize, limit: usize) -> Self {
+        Timeline {
+            interval: interval.max(1),
+            limit: limit.max(2),
+            checkpoints: Vec::new(),
+            head: None,
+            latest: 0,
+            size: (0, 0),
+        }
+    }
+
+    /// generations between checkpoints, which doubles every time they're thinned out
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+
+    /// how many checkpoints are kept before they're thinned out, see `Timeline`
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn checkpoints(&self) -> &[Checkpoint] {
+        &self.checkpoints
+    }
+
+    /// the first and the furthest generation that can be gone back to, `None` if
+    /// nothing has been recorded
+    pub fn range(&self) -> Option<(usize, usize)> {
+        self.checkpoints
+            .first()
+            .map(|first| (first.generation, self.latest))
+    }
+
+    /// forgets everything, for when the universe the roots belong to is replaced
+    pub fn clear(&mut self) {
+        self.checkpoints.clear();
+        self.head = None;
+        self.latest = 0;
+    }
+
+    /// takes note of the board as it is now. to be called after every step and
+    /// after edits, a board that changed without its generation going up is taken to
+    /// be an edit. resizing the board starts the timeline over
+    pub fn record(&mut self, universe: &Universe) {
+        let (generation, root) = (universe.generation(), universe.root());
+        let size = (universe.width(), universe.height());
+        if size != self.size {
+            self.clear();
+            self.size = size;
+        }
+
+        match self.head {
+            Some(head) if head == (generation, root) => return,
+            Some((stepped_from, _)) if generation > stepped_from => {
+                // stepping from before an edit goes somewhere else than the edit did
+                let diverged = self.checkpoints.iter().any(|checkpoint| {
+                    checkpoint.generation == generation && checkpoint.root != root
+                });
+                if diverged {
+                    self.truncate(generation);
+                }
+                let due = self
+                    .before(generation)
+                    .is_none_or(|previous| generation >= previous.generation + self.interval);
+                if due {
+                    self.insert(Checkpoint {
+                        generation,
+                        root,
+                        edited: false,
+                    });
+                }
+                self.latest = self.latest.max(generation);
+            }
+            _ => {
+                // an edit, which nothing after it can follow on from
+                self.truncate(generation);
+                self.checkpoints.push(Checkpoint {
+                    generation,
+                    root,
+                    edited: true,
+                });
+            }
+        }
+        self.head = Some((generation, root));
+        self.thin();
+    }
+
+    /// puts `universe` back to how it was in `generation`, simulating forward from
+    /// the checkpoint before it. edits since the last record are recorded first
+    pub fn seek(&mut self, universe: &mut Universe, generation: usize) -> Result<(), &'static str> {
+        self.record(universe);
+        let checkpoint = self
+            .before(generation)
+            .ok_or("generation is before the start of the timeline")?;
+        universe.rewind(checkpoint.root, checkpoint.generation)?;
+        self.head = Some((checkpoint.generation, checkpoint.root));
+        for _ in checkpoint.generation..generation {
+            universe.step();
+            self.record(universe);
+        }
+        Ok(())
+    }
+
+    /// goes back a single generation
+    pub fn step_back(&mut self, universe: &mut Universe) -> Result<(), &'static str> {
+        match universe.generation().checked_sub(1) {
+            Some(generation) => self.seek(universe, generation),
+            None => Err("already at generation 0"),
+        }
+    }
+
+    /// the last checkpoint at or before `generation`
+    fn before(&self, generation: usize) -> Option<Checkpoint> {
+        self.checkpoints
+            .iter()
+            .rev()
+            .find(|checkpoint| checkpoint.generation <= generation)
+            .copied()
+    }
+
+    /// forgets `generation` and everything after it
+    fn truncate(&mut self, generation: usize) {
+        self.checkpoints
+            .retain(|checkpoint| checkpoint.generation < generation);
+        self.latest = generation;
+    }
+
+    fn insert(&mut self, checkpoint: Checkpoint) {
+        match self
+            .checkpoints
+            .binary_search_by_key(&checkpoint.generation, |checkpoint| checkpoint.generation)
+        {
+            Ok(index) => self.checkpoints[index] = checkpoint,
+            Err(index) => self.checkpoints.insert(index, checkpoint),
+        }
+    }
+
+    /// drops every other checkpoint until there are few enough. edits and the last
+    /// checkpoint are always kept
+    fn thin(&mut self) {
+        while self.checkpoints.len() > self.limit {
+            let (count, last) = (self.checkpoints.len(), self.checkpoints.len() - 1);
+            let mut stepped = 0;
+            let mut index = 0;
+            self.checkpoints.retain(|checkpoint| {
+                let keep = checkpoint.edited || index == last || {
+                    stepped += 1;
+                    stepped % 2 == 1
+                };
+                index += 1;
+                keep
+            });
+            self.interval *= 2;
+            if self.checkpoints.len() == count {
+                break;
+            }
+        }
+    }
+}
@@ -1,6 +1,22 @@
-
 extern crate wasm_gameoflife;
-use wasm_gameoflife::universe::Universe;
+use wasm_gameoflife::formats::apgcode::{self, Apgcode};
+use wasm_gameoflife::formats::{self, life105, life106, plaintext, rle, Format, FormatError};
+use wasm_gameoflife::library;
+use wasm_gameoflife::render::animation::{self, AnimationOptions, Crop};
+use wasm_gameoflife::render::frame::{Frame, Overlay, MAX_DIRTY_RECTS};
+use wasm_gameoflife::render::svg::{self, Highlight, SvgOptions};
+use wasm_gameoflife::render::{
+    self, Color, RenderOptions, ALIVE_COLOR, DEAD_COLOR, ENVELOPE_COLOR, GRID_COLOR,
+};
+use wasm_gameoflife::search::{self, SoupSearch};
+use wasm_gameoflife::session::Session;
+use wasm_gameoflife::universe::census::ObjectKind;
+use wasm_gameoflife::universe::pattern::{PasteMode, Pattern, Transform};
+use wasm_gameoflife::universe::rect::{self, Rect};
+use wasm_gameoflife::universe::snapshot::SnapshotNode;
+use wasm_gameoflife::universe::soup::{self, Symmetry};
+use wasm_gameoflife::universe::timeline::Timeline;
+use wasm_gameoflife::universe::{Anchor, Universe};
 
 #[cfg(test)]
 pub fn input_spaceship() -> Universe {
@@ -29,3 +45,1890 @@ pub fn test_tick() {
     input_universe.step();
     assert_eq!(&input_universe.get_cells(), &expected_universe.get_cells());
 }
+
+#[test]
+pub fn test_non_square_size() {
+    let universe = Universe::new(100, 60);
+    assert_eq!(universe.width(), 100);
+    assert_eq!(universe.height(), 60);
+    assert_eq!(universe.size(), 128);
+}
+
+#[test]
+pub fn test_wrap_non_square() {
+    // a blinker lying across the east / west edge
+    let mut input_universe = Universe::new(100, 60);
+    input_universe.set_cells(&[(99, 10), (0, 10), (1, 10)]);
+    let mut expected_universe = Universe::new(100, 60);
+    expected_universe.set_cells(&[(0, 9), (0, 10), (0, 11)]);
+    input_universe.step();
+    assert_eq!(&input_universe.get_cells(), &expected_universe.get_cells());
+
+    // a blinker lying across the north / south edge
+    let mut input_universe = Universe::new(100, 60);
+    input_universe.set_cells(&[(5, 59), (5, 0), (5, 1)]);
+    let mut expected_universe = Universe::new(100, 60);
+    expected_universe.set_cells(&[(4, 0), (5, 0), (6, 0)]);
+    input_universe.step();
+    assert_eq!(&input_universe.get_cells(), &expected_universe.get_cells());
+}
+
+#[test]
+pub fn test_wrap_corners() {
+    // a block split over all four corners is still a block
+    for (w, h) in [(100, 60), (64, 64)].iter().cloned() {
+        let mut universe = Universe::new(w, h);
+        universe.set_cells(&[(w - 1, h - 1), (0, h - 1), (w - 1, 0), (0, 0)]);
+        let expected = universe.get_cells();
+        let before = universe.root();
+        universe.step();
+        assert_eq!(&universe.get_cells(), &expected);
+        assert!(universe.diff(before, universe.root()).is_empty());
+    }
+}
+
+#[test]
+pub fn test_glider_laps_non_square() {
+    // a glider moves one cell diagonally every 4 generations, so it gets back to
+    // where it started after 4 * lcm(100, 60) generations
+    let mut universe = Universe::new(100, 60);
+    universe.set_cells(&[(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)]);
+    let expected = universe.get_cells();
+    for _ in 0..1200 {
+        universe.step();
+        assert_eq!(universe.get_cells().count_ones(), 5);
+    }
+    assert_eq!(&universe.get_cells(), &expected);
+}
+
+#[test]
+pub fn test_step_by() {
+    // boards filling the root, smaller ones, and ones thinner than a jump
+    for &(width, height) in &[(32, 32), (64, 64), (100, 60), (40, 7), (3, 20)] {
+        let mut universe = Universe::new(width, height);
+        universe.randomize_with("jump", 0.4, None, Symmetry::C1);
+        let mut expected = universe.clone();
+        for &generations in &[1, 2, 5, 16, 37, 64] {
+            universe.step_by(generations);
+            for _ in 0..generations {
+                expected.step();
+            }
+            assert_eq!(
+                live_cells(&universe),
+                live_cells(&expected),
+                "{}x{} board after {} generations",
+                width,
+                height,
+                expected.generation()
+            );
+            assert_eq!(universe.generation(), expected.generation());
+        }
+    }
+
+    let mut universe = Universe::new(100, 60);
+    universe.set_cells(&[(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)]);
+    let expected = universe.get_cells();
+    universe.step_by(1200);
+    assert_eq!(&universe.get_cells(), &expected);
+}
+
+#[cfg(test)]
+fn assert_get_cell_matches_get_cells(universe: &Universe) {
+    let cells = universe.get_cells();
+    for y in 0..universe.height() {
+        for x in 0..universe.width() {
+            assert_eq!(
+                universe.get_cell(x, y),
+                Ok(cells[universe.get_morton(x, y)]),
+                "cell ({}, {})",
+                x,
+                y
+            );
+        }
+    }
+}
+
+#[test]
+pub fn test_get_cell_matches_get_cells() {
+    for (w, h) in [(64, 64), (100, 60), (17, 40)].iter().cloned() {
+        let mut universe = Universe::new(w, h);
+        universe.randomize();
+        assert_get_cell_matches_get_cells(&universe);
+        universe.step();
+        assert_get_cell_matches_get_cells(&universe);
+    }
+}
+
+#[test]
+pub fn test_cell_coordinates() {
+    let mut universe = Universe::new(100, 60);
+    universe.set_cells(&[(70, 3), (2, 50)]);
+    assert_eq!(universe.get_cell(70, 3), Ok(true));
+    assert_eq!(universe.get_cell(3, 70), Err("y out of range for height"));
+    assert_eq!(universe.get_cell(2, 50), Ok(true));
+    assert_eq!(universe.get_cell(50, 2), Ok(false));
+    assert_eq!(universe.get_cell(100, 0), Err("x out of range for width"));
+
+    universe.toggle_cell(70, 3);
+    universe.toggle_cell(99, 59);
+    assert_eq!(universe.get_cell(70, 3), Ok(false));
+    assert_eq!(universe.get_cell(99, 59), Ok(true));
+    assert_get_cell_matches_get_cells(&universe);
+}
+
+#[cfg(test)]
+fn live_cells(universe: &Universe) -> Vec<(usize, usize)> {
+    let mut cells = vec![];
+    for y in 0..universe.height() {
+        for x in 0..universe.width() {
+            if universe.get_cell(x, y) == Ok(true) {
+                cells.push((x, y));
+            }
+        }
+    }
+    cells
+}
+
+#[test]
+pub fn test_resize_keeps_cells() {
+    let glider = [(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)];
+    let shifted = |dx: usize, dy: usize| -> Vec<(usize, usize)> {
+        let mut cells: Vec<_> = glider.iter().map(|&(x, y)| (x + dx, y + dy)).collect();
+        cells.sort_by_key(|&(x, y)| (y, x));
+        cells
+    };
+
+    for (anchor, dx, dy) in [
+        (Anchor::TopLeft, 0, 0),
+        (Anchor::TopRight, 280, 0),
+        (Anchor::BottomLeft, 0, 130),
+        (Anchor::BottomRight, 280, 130),
+        (Anchor::Center, 140, 65),
+    ]
+    .iter()
+    .cloned()
+    {
+        let mut universe = Universe::new(20, 20);
+        universe.set_cells(&glider);
+        universe.resize(300, 150, anchor);
+        assert_eq!(universe.width(), 300);
+        assert_eq!(universe.height(), 150);
+        assert_eq!(universe.size(), 512);
+        assert_eq!(live_cells(&universe), shifted(dx, dy), "{:?}", anchor);
+
+        // and back again
+        universe.resize(20, 20, anchor);
+        assert_eq!(universe.size(), 32);
+        assert_eq!(live_cells(&universe), shifted(0, 0), "{:?}", anchor);
+    }
+}
+
+#[test]
+pub fn test_resize_crops() {
+    let mut universe = Universe::new(64, 64);
+    universe.set_cells(&[(1, 1), (40, 2), (3, 50), (60, 60), (40, 63)]);
+    universe.set_width(41);
+    assert_eq!(
+        live_cells(&universe),
+        vec![(1, 1), (40, 2), (3, 50), (40, 63)]
+    );
+    universe.resize(20, 20, Anchor::BottomRight);
+    assert_eq!(live_cells(&universe), vec![(19, 19)]);
+
+    // cropped cells don't come back after stepping
+    let mut universe = Universe::new(64, 64);
+    universe.set_cells(&[(28, 10), (29, 10), (30, 10), (31, 10), (32, 10)]);
+    universe.set_width(31);
+    let mut expected = Universe::new(31, 64);
+    expected.set_cells(&[(28, 10), (29, 10), (30, 10)]);
+    universe.step();
+    expected.step();
+    assert_eq!(live_cells(&universe), live_cells(&expected));
+    assert_eq!(live_cells(&universe), vec![(29, 9), (29, 10), (29, 11)]);
+}
+
+#[test]
+pub fn test_crop_to_bounding_box() {
+    let mut universe = Universe::new(100, 60);
+    universe.crop_to_bounding_box();
+    assert_eq!((universe.width(), universe.height()), (100, 60));
+
+    universe.set_cells(&[(51, 22), (52, 23), (53, 21), (53, 22), (53, 23)]);
+    universe.crop_to_bounding_box();
+    assert_eq!((universe.width(), universe.height()), (3, 3));
+    assert_eq!(
+        live_cells(&universe),
+        vec![(2, 0), (0, 1), (2, 1), (1, 2), (2, 2)]
+    );
+}
+
+#[test]
+pub fn test_bounding_box() {
+    let mut universe = Universe::new(100, 60);
+    assert_eq!(universe.bounding_box(), None);
+
+    universe.set_cells(&[(42, 17)]);
+    assert_eq!(universe.bounding_box(), Some(Rect::new(42, 17, 1, 1)));
+
+    universe.set_cells(&[(3, 40), (99, 18), (50, 59)]);
+    assert_eq!(universe.bounding_box(), Some(Rect::new(3, 17, 97, 43)));
+
+    // matches a scan over every cell
+    universe.randomize();
+    let cells = live_cells(&universe);
+    let left = cells.iter().map(|c| c.0).min().unwrap();
+    let right = cells.iter().map(|c| c.0).max().unwrap() + 1;
+    let top = cells.iter().map(|c| c.1).min().unwrap();
+    let bottom = cells.iter().map(|c| c.1).max().unwrap() + 1;
+    assert_eq!(
+        universe.bounding_box(),
+        Some(Rect::new(left, top, right - left, bottom - top))
+    );
+
+    universe.clear();
+    assert_eq!(universe.bounding_box(), None);
+}
+
+#[test]
+pub fn test_live_cells() {
+    let mut universe = Universe::new(100, 60);
+    assert_eq!(universe.live_cells().count(), 0);
+
+    universe.randomize();
+    let mut cells: Vec<_> = universe.live_cells().collect();
+    cells.sort_by_key(|&(x, y)| (y, x));
+    assert_eq!(cells, live_cells(&universe));
+
+    let rect = Rect::new(13, 7, 50, 21);
+    let mut cells: Vec<_> = universe.live_cells_in(rect).collect();
+    cells.sort_by_key(|&(x, y)| (y, x));
+    let expected: Vec<_> = live_cells(&universe)
+        .into_iter()
+        .filter(|&(x, y)| rect.contains(x, y))
+        .collect();
+    assert_eq!(cells, expected);
+
+    // cells come out in morton order
+    let indices: Vec<_> = universe
+        .live_cells()
+        .map(|(x, y)| universe.get_morton(x, y))
+        .collect();
+    assert!(indices.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+pub fn test_copy_region() {
+    let mut universe = Universe::new(100, 60);
+    universe.set_cells(&[(10, 10), (11, 12), (30, 40), (98, 58)]);
+
+    let pattern = universe.copy_region(Rect::new(9, 9, 5, 5));
+    assert_eq!((pattern.width(), pattern.height()), (5, 5));
+    assert_eq!(pattern.cells(), &[(1, 1), (2, 3)]);
+
+    // regions hanging off the board keep their size
+    let pattern = universe.copy_region(Rect::new(95, 55, 10, 10));
+    assert_eq!((pattern.width(), pattern.height()), (10, 10));
+    assert_eq!(pattern.cells(), &[(3, 3)]);
+
+    assert!(universe.copy_region(Rect::new(50, 10, 10, 10)).is_empty());
+}
+
+#[test]
+pub fn test_clear_region() {
+    let mut universe = Universe::new(100, 60);
+    universe.randomize();
+    let before = live_cells(&universe);
+
+    let rect = Rect::new(7, 3, 61, 29);
+    universe.clear_region(rect);
+    let expected: Vec<_> = before
+        .into_iter()
+        .filter(|&(x, y)| !rect.contains(x, y))
+        .collect();
+    assert_eq!(live_cells(&universe), expected);
+}
+
+#[test]
+pub fn test_paste_modes() {
+    // pattern:   board under it:
+    //   ##.        #.#
+    //   ...        #..
+    let pattern = Pattern::with_cells(3, 2, &[(0, 0), (1, 0)]);
+    let board = [(10, 10), (12, 10), (10, 11)];
+    let outside = [(9, 10), (13, 11), (10, 12)];
+
+    for (mode, expected) in [
+        (PasteMode::Or, vec![(10, 10), (11, 10), (12, 10), (10, 11)]),
+        (PasteMode::Copy, vec![(10, 10), (11, 10)]),
+        (PasteMode::Xor, vec![(11, 10), (12, 10), (10, 11)]),
+        (PasteMode::And, vec![(10, 10)]),
+    ]
+    .iter()
+    .cloned()
+    {
+        let mut universe = Universe::new(100, 60);
+        universe.set_cells(&board);
+        universe.set_cells(&outside);
+        universe.paste(&pattern, (10, 10), mode);
+
+        let mut expected = expected;
+        expected.extend_from_slice(&outside);
+        expected.sort_by_key(|&(x, y)| (y, x));
+        assert_eq!(live_cells(&universe), expected, "{:?}", mode);
+    }
+}
+
+#[test]
+pub fn test_paste_round_trip() {
+    let mut universe = Universe::new(100, 60);
+    universe.randomize();
+    let pattern = universe.copy_region(Rect::new(0, 0, 40, 30));
+
+    let mut other = Universe::new(100, 60);
+    other.paste(&pattern, (55, 25), PasteMode::Copy);
+    assert_eq!(other.copy_region(Rect::new(55, 25, 40, 30)), pattern);
+    assert_eq!(other.live_cells().count(), pattern.population());
+
+    // pasting over itself with xor leaves nothing behind
+    other.paste(&pattern, (55, 25), PasteMode::Xor);
+    assert_eq!(other.bounding_box(), None);
+
+    // pastes are clipped to the board
+    other.paste(&pattern, (80, 50), PasteMode::Or);
+    let clipped = pattern.cells().iter().filter(|&&(x, y)| x < 20 && y < 10);
+    assert_eq!(other.live_cells().count(), clipped.count());
+}
+
+#[test]
+pub fn test_pattern_transforms() {
+    // .#
+    // ..
+    // ##
+    let pattern = Pattern::with_cells(2, 3, &[(1, 0), (0, 2), (1, 2)]);
+
+    let rotated = pattern.transform(Transform::Rotate90);
+    assert_eq!((rotated.width(), rotated.height()), (3, 2));
+    assert_eq!(rotated.cells(), &[(0, 0), (0, 1), (2, 1)]);
+
+    let mut turned = pattern.clone();
+    for _ in 0..4 {
+        turned = turned.transform(Transform::Rotate90);
+    }
+    assert_eq!(turned, pattern);
+    assert_eq!(
+        rotated.transform(Transform::Rotate90),
+        pattern.transform(Transform::Rotate180)
+    );
+    assert_eq!(
+        rotated.transform(Transform::FlipHorizontal),
+        pattern.transform(Transform::Transpose)
+    );
+    assert_eq!(
+        rotated.transform(Transform::FlipVertical),
+        pattern.transform(Transform::AntiTranspose)
+    );
+
+    for transform in Transform::ALL.iter().cloned() {
+        let moved = pattern.transform(transform);
+        assert_eq!(moved.population(), 3);
+        assert_eq!(moved.canonical(), pattern.canonical());
+    }
+
+    // canonical forms ignore empty margins
+    let padded = Pattern::with_cells(6, 6, &[(3, 1), (2, 3), (3, 3)]);
+    assert_eq!(padded.trimmed(), pattern);
+    assert_eq!(padded.canonical(), pattern.canonical());
+}
+
+#[test]
+pub fn test_glider_directions() {
+    // heads south east
+    let glider = Pattern::with_cells(3, 3, &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+
+    for (transform, dx, dy) in [
+        (Transform::Identity, 1, 1),
+        (Transform::Rotate90, -1, 1),
+        (Transform::Rotate180, -1, -1),
+        (Transform::Rotate270, 1, -1),
+    ]
+    .iter()
+    .cloned()
+    {
+        let mut universe = Universe::new(64, 64);
+        universe.paste_transformed(&glider, (30, 30), transform, PasteMode::Or);
+        let before = universe.bounding_box().unwrap();
+        for _ in 0..4 {
+            universe.step();
+        }
+        let after = universe.bounding_box().unwrap();
+        assert_eq!(
+            (
+                after.x() as isize - before.x() as isize,
+                after.y() as isize - before.y() as isize
+            ),
+            (dx, dy),
+            "{:?}",
+            transform
+        );
+    }
+}
+
+#[test]
+pub fn test_rle_round_trip() {
+    let pattern = rle::read("#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!").unwrap();
+    assert_eq!((pattern.width(), pattern.height()), (3, 3));
+    assert_eq!(pattern.cells(), &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+    assert_eq!(
+        rle::write(&pattern),
+        "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n"
+    );
+
+    let mut universe = Universe::new(100, 60);
+    universe.randomize();
+    let pattern = universe.copy_region(Rect::new(0, 0, 100, 60));
+    let text = rle::write(&pattern);
+    assert!(text.lines().all(|line| line.len() <= 70));
+    assert_eq!(rle::read(&text), Ok(pattern));
+}
+
+#[test]
+pub fn test_rle_errors() {
+    assert_eq!(
+        rle::read("x = 3\nbo$2bo$3o!"),
+        Err(FormatError::InvalidHeader("x = 3".to_string()))
+    );
+    assert_eq!(
+        rle::read("x = 3, y = 3\nbo$2bo$\n3oz!"),
+        Err(FormatError::UnexpectedCharacter {
+            character: 'z',
+            line: 3
+        })
+    );
+    assert_eq!(
+        rle::read("99999999999999999999999o!"),
+        Err(FormatError::NumberTooLarge { line: 1 })
+    );
+    // cells past the header size are off the board, runs can't overflow
+    assert_eq!(
+        rle::read("x = 1, y = 1\n3o!"),
+        Err(FormatError::OutOfRange { x: 1, y: 0 })
+    );
+    assert_eq!(
+        rle::read("x = 3, y = 3\n2$o!"),
+        Ok(Pattern::with_cells(3, 3, &[(0, 2)]))
+    );
+    assert_eq!(
+        rle::read("x = 3, y = 3\n3$o!"),
+        Err(FormatError::OutOfRange { x: 0, y: 3 })
+    );
+    assert_eq!(
+        rle::read("x = 3, y = 3\n18446744073709551615bo!"),
+        Err(FormatError::NumberTooLarge { line: 2 })
+    );
+    assert_eq!(
+        rle::read("x = 3, y = 3\nbo18446744073709551615o!"),
+        Err(FormatError::NumberTooLarge { line: 2 })
+    );
+    assert_eq!(
+        rle::read("x = 3, y = 3\n1000000000000o!"),
+        Err(FormatError::OutOfRange { x: 3, y: 0 })
+    );
+    // without a header the pattern grows to fit, unless it's read within a size
+    let pattern = rle::read("3o!").unwrap();
+    assert_eq!((pattern.width(), pattern.height()), (3, 1));
+    assert_eq!(
+        rle::read_within("5$3o!", 8, 4),
+        Err(FormatError::OutOfRange { x: 0, y: 5 })
+    );
+    assert_eq!(
+        rle::read_within("x = 20, y = 1\n10o!", 8, 4),
+        Err(FormatError::OutOfRange { x: 8, y: 0 })
+    );
+}
+
+#[test]
+pub fn test_library_patterns() {
+    for entry in library::PATTERNS {
+        let pattern = entry.pattern();
+        assert_eq!(
+            (pattern.width(), pattern.height()),
+            (entry.width, entry.height),
+            "{}",
+            entry.name
+        );
+        assert_eq!(pattern.trimmed(), pattern, "{}", entry.name);
+
+        let period = match entry.period {
+            Some(period) => period,
+            None => continue,
+        };
+        let mut universe = Universe::new(128, 128);
+        universe.paste(&pattern, (60, 60), PasteMode::Or);
+        let start: Vec<_> = universe.live_cells().collect();
+        for _ in 0..period {
+            universe.step();
+        }
+        let (dx, dy) = entry.displacement;
+        let (x, y) = ((60 + dx) as usize, (60 + dy) as usize);
+        let mut expected: Vec<_> = start
+            .iter()
+            .map(|&(x, y)| ((x as isize + dx) as usize, (y as isize + dy) as usize))
+            .collect();
+        expected.sort_by_key(|&(x, y)| (y, x));
+        // guns leave what they fire outside of their own bounding box
+        let mut cells: Vec<_> = universe
+            .live_cells_in(Rect::new(x, y, entry.width, entry.height))
+            .collect();
+        cells.sort_by_key(|&(x, y)| (y, x));
+        assert_eq!(cells, expected, "{}", entry.name);
+    }
+
+    assert_eq!(
+        library::find("glider").unwrap().speed(),
+        Some("c/4".to_string())
+    );
+    assert_eq!(
+        library::find("LWSS").unwrap().speed(),
+        Some("c/2".to_string())
+    );
+    assert_eq!(library::find("pulsar").unwrap().speed(), None);
+    assert!(library::find("not a pattern").is_none());
+}
+
+#[test]
+pub fn test_diehard_dies() {
+    let mut universe = Universe::new(128, 128);
+    universe.paste(
+        &library::find("Diehard").unwrap().pattern(),
+        (60, 60),
+        PasteMode::Or,
+    );
+    for _ in 0..129 {
+        universe.step();
+    }
+    assert!(universe.bounding_box().is_some());
+    universe.step();
+    assert_eq!(universe.bounding_box(), None);
+}
+
+#[test]
+pub fn test_soup_reproducible() {
+    let mut a = Universe::new(64, 64);
+    let mut b = Universe::new(64, 64);
+    a.randomize_with("k_soup", 0.5, None, Symmetry::C1);
+    b.randomize_with("k_soup", 0.5, None, Symmetry::C1);
+    assert_eq!(live_cells(&a), live_cells(&b));
+    assert!(!live_cells(&a).is_empty());
+
+    b.randomize_with("k_soup2", 0.5, None, Symmetry::C1);
+    assert_ne!(live_cells(&a), live_cells(&b));
+
+    // fixed output, so the generator can't change under us between platforms
+    assert_eq!(
+        rle::write(&soup::soup("abc", 0.5, 4, 4, Symmetry::C1)),
+        "x = 4, y = 4, rule = B3/S23\nob2o$o2bo$ob2o$obo!\n"
+    );
+    assert!(soup::soup("abc", 0.0, 16, 16, Symmetry::C1).is_empty());
+    assert!(soup::soup("abc", f64::NAN, 16, 16, Symmetry::C1).is_empty());
+    assert!(soup::soup("abc", f64::INFINITY, 16, 16, Symmetry::C1).is_empty());
+    assert_eq!(
+        soup::soup("abc", 1.0, 16, 16, Symmetry::C1).population(),
+        256
+    );
+}
+
+#[test]
+pub fn test_soup_region() {
+    let mut universe = Universe::new(64, 64);
+    universe.set_cells(&[(0, 0), (40, 40)]);
+    universe.randomize_with("region", 1.0, Some(Rect::new(10, 10, 16, 8)), Symmetry::C1);
+    assert_eq!(universe.bounding_box(), Some(Rect::new(0, 0, 41, 41)));
+    assert_eq!(universe.live_cells().count(), 16 * 8 + 2);
+
+    // copy mode clears what was under the region
+    universe.randomize_with("region", 0.0, Some(Rect::new(0, 0, 20, 20)), Symmetry::C1);
+    assert_eq!(universe.live_cells().count(), 16 * 8 - 10 * 8 + 1);
+}
+
+#[test]
+pub fn test_soup_symmetries() {
+    assert_eq!(Symmetry::C2_1.fit(16, 16), (15, 15));
+    assert_eq!(Symmetry::C2_2.fit(16, 16), (16, 15));
+    assert_eq!(Symmetry::C4_4.fit(16, 13), (12, 12));
+    assert_eq!(Symmetry::D2_X.fit(16, 13), (13, 13));
+
+    for symmetry in Symmetry::ALL.iter().cloned() {
+        assert_eq!(symmetry.name().parse::<Symmetry>(), Ok(symmetry));
+
+        let pattern = soup::soup("symmetric", 0.5, 16, 16, symmetry);
+        let (width, height) = symmetry.fit(16, 16);
+        assert_eq!((pattern.width(), pattern.height()), (width, height));
+        for transform in symmetry.transforms() {
+            assert_eq!(
+                &pattern.transform(*transform),
+                &pattern,
+                "{} {:?}",
+                symmetry,
+                transform
+            );
+        }
+    }
+    assert!("C3".parse::<Symmetry>().is_err());
+}
+
+#[test]
+pub fn test_wechsler() {
+    let block = Pattern::from_cells(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+    assert_eq!(apgcode::wechsler(&block), "33");
+    let spaced = Pattern::from_cells(&[(0, 0), (5, 0), (50, 6)]);
+    assert_eq!(apgcode::wechsler(&spaced), "1y01zyzy72");
+    let glider = library::find("glider").unwrap().pattern();
+    assert_eq!(apgcode::canonical_wechsler(&[glider]), "153");
+}
+
+#[test]
+pub fn test_census() {
+    let mut universe = Universe::new(96, 96);
+    let place = |universe: &mut Universe, name: &str, at| {
+        universe.paste(&library::find(name).unwrap().pattern(), at, PasteMode::Or)
+    };
+    place(&mut universe, "glider", (5, 5));
+    place(&mut universe, "LWSS", (40, 5));
+    place(&mut universe, "pulsar", (5, 40));
+    // blinker, and two blocks close enough to be grouped
+    universe.set_cells(&[(60, 60), (61, 60), (62, 60)]);
+    universe.set_cells(&[(70, 70), (71, 70), (70, 71), (71, 71)]);
+    universe.set_cells(&[(74, 70), (75, 70), (74, 71), (75, 71)]);
+
+    let census = universe.census();
+    assert_eq!(census.total(), 6);
+    assert_eq!(census.get("xs4_33").unwrap().count, 2);
+    assert_eq!(census.get("xs4_33").unwrap().kind, ObjectKind::StillLife);
+    assert_eq!(census.get("xp2_7").unwrap().period, Some(2));
+    assert_eq!(census.get("xq4_153").unwrap().kind, ObjectKind::Spaceship);
+    assert_eq!(census.get("xq4_6frc").unwrap().period, Some(4));
+    let pulsar = census
+        .get("xp3_co9nas0san9oczgoldlo0oldlogz1047210127401")
+        .unwrap();
+    assert_eq!(pulsar.kind, ObjectKind::Oscillator);
+    assert_eq!(census.count(ObjectKind::Spaceship), 2);
+    assert_eq!(census.by_frequency()[0].apgcode, "xs4_33");
+
+    // a traffic light is four blinkers, but pieces that need each other stay together
+    let mut universe = Universe::new(32, 32);
+    let traffic_light = rle::read("2b3o2$o5bo$o5bo$o5bo2$2b3o!").unwrap();
+    universe.paste(&traffic_light, (10, 10), PasteMode::Or);
+    assert_eq!(universe.census().get("xp2_7").unwrap().count, 4);
+    universe.clear();
+    universe.paste(
+        &library::find("LWSS").unwrap().pattern(),
+        (10, 10),
+        PasteMode::Or,
+    );
+    assert_eq!(universe.census().total(), 1);
+
+    assert!(Universe::new(16, 16).census().is_empty());
+}
+
+#[test]
+pub fn test_apgcode_round_trip() {
+    let glider = apgcode::read("xq4_153").unwrap();
+    assert_eq!(glider.cells(), &[(0, 0), (1, 0), (2, 0), (2, 1), (1, 2)]);
+
+    for code in &[
+        "xs4_33",
+        "xs6_356",
+        "xp2_7",
+        "xq4_6frc",
+        "xp3_co9nas0san9oczgoldlo0oldlogz1047210127401",
+    ] {
+        let apgcode: Apgcode = code.parse().unwrap();
+        assert_eq!(apgcode.to_string(), *code);
+
+        let mut universe = Universe::new(32, 32);
+        universe.set_apgcode(&apgcode, (10, 10));
+        assert_eq!(universe.get_apgcode(Rect::new(0, 0, 32, 32)), Some(apgcode));
+    }
+
+    let pulsar: Apgcode = "xp3_co9nas0san9oczgoldlo0oldlogz1047210127401"
+        .parse()
+        .unwrap();
+    assert_eq!(pulsar.kind(), ObjectKind::Oscillator);
+    assert_eq!(pulsar.period(), 3);
+    assert_eq!(pulsar.population(), None);
+    assert_eq!(pulsar.pattern().population(), 72);
+    let ship: Apgcode = "xs6_356".parse().unwrap();
+    assert_eq!(ship.population(), Some(6));
+    assert_eq!(ship.period(), 1);
+
+    // the code doesn't depend on the phase or orientation it's found in
+    let mut universe = Universe::new(32, 32);
+    let lwss = library::find("LWSS").unwrap().pattern();
+    universe.paste_transformed(&lwss, (10, 10), Transform::Rotate90, PasteMode::Or);
+    universe.step();
+    assert_eq!(
+        universe
+            .get_apgcode(Rect::new(0, 0, 32, 32))
+            .unwrap()
+            .to_string(),
+        "xq4_6frc"
+    );
+    assert_eq!(
+        Universe::new(16, 16).get_apgcode(Rect::new(0, 0, 16, 16)),
+        None
+    );
+}
+
+#[test]
+pub fn test_apgcode_errors() {
+    let invalid = |code: &str| Err(FormatError::InvalidHeader(code.to_string()));
+    assert_eq!(apgcode::read("33"), invalid("33"));
+    assert_eq!(apgcode::read("xz4_33"), invalid("xz4_33"));
+    assert_eq!(apgcode::read("xs_33"), invalid("xs_33"));
+    assert_eq!(apgcode::read("xs5_33"), invalid("xs5_33"));
+    assert_eq!(apgcode::read("xp0_7"), invalid("xp0_7"));
+    assert_eq!(
+        apgcode::read("xs4_3!"),
+        Err(FormatError::UnexpectedCharacter {
+            character: '!',
+            line: 1
+        })
+    );
+    assert_eq!(
+        apgcode::read("xp2_7y"),
+        Err(FormatError::UnexpectedEnd { line: 1 })
+    );
+    assert_eq!(
+        apgcode::decode("1y01zyzy72").unwrap().cells(),
+        &[(0, 0), (5, 0), (50, 6)]
+    );
+}
+
+#[test]
+pub fn test_soup_search() {
+    let census = search::run_soup("k_test0", Symmetry::C1).unwrap();
+    assert_eq!(Some(census), search::run_soup("k_test0", Symmetry::C1));
+
+    let mut straight = SoupSearch::new("k_test", Symmetry::C1).unwrap();
+    straight.run(4);
+    assert_eq!(straight.soups(), 4);
+    assert_eq!(straight.next_seed(), "k_test4");
+    assert!(straight.census().total() > 0);
+    assert!(straight
+        .report()
+        .starts_with("4 soups of C1 with seeds k_test0 to 3\n"));
+
+    // stopping and picking back up gives the same result
+    let mut resumed = SoupSearch::new("k_test", Symmetry::C1).unwrap();
+    resumed.run(2);
+    let mut resumed = SoupSearch::from_checkpoint(&resumed.checkpoint()).unwrap();
+    resumed.run(2);
+    assert_eq!(resumed, straight);
+    assert_eq!(
+        SoupSearch::from_checkpoint(&straight.checkpoint()).unwrap(),
+        straight
+    );
+}
+
+#[test]
+pub fn test_checkpoint_errors() {
+    assert_eq!(
+        SoupSearch::from_checkpoint("x = 3, y = 3"),
+        Err(FormatError::InvalidHeader("x = 3, y = 3".to_string()))
+    );
+    assert_eq!(
+        SoupSearch::from_checkpoint(""),
+        Err(FormatError::UnexpectedEnd { line: 1 })
+    );
+    assert_eq!(
+        SoupSearch::from_checkpoint("#soup-search\nprefix k_\nsymmetry C3"),
+        Err(FormatError::InvalidLine {
+            line: 3,
+            text: "symmetry C3".to_string()
+        })
+    );
+    assert_eq!(
+        SoupSearch::from_checkpoint("#soup-search\nprefix k_\n\nsoups lots"),
+        Err(FormatError::InvalidLine {
+            line: 4,
+            text: "soups lots".to_string()
+        })
+    );
+    assert_eq!(
+        SoupSearch::from_checkpoint("#soup-search\nprefix k_\nobject xs5_33 1"),
+        Err(FormatError::InvalidLine {
+            line: 3,
+            text: "object xs5_33 1".to_string()
+        })
+    );
+    assert_eq!(
+        SoupSearch::from_checkpoint("#soup-search\nsoups 3"),
+        Err(FormatError::UnexpectedEnd { line: 2 })
+    );
+
+    // prefixes that couldn't be read back from a checkpoint aren't allowed
+    assert!(SoupSearch::new("", Symmetry::C1).is_err());
+    assert!(SoupSearch::new("a b", Symmetry::C1).is_err());
+    assert!(SoupSearch::new("k_\t", Symmetry::C1).is_err());
+}
+
+#[test]
+pub fn test_generation() {
+    let mut universe = Universe::new(32, 32);
+    assert_eq!(universe.generation(), 0);
+    universe.step();
+    universe.step();
+    assert_eq!(universe.generation(), 2);
+    universe.reset();
+    assert_eq!(universe.generation(), 0);
+}
+
+#[test]
+pub fn test_snapshot_round_trip() {
+    let mut universe = Universe::new(50, 30);
+    universe.randomize_with("snapshot", 0.4, None, Symmetry::C1);
+    for _ in 0..3 {
+        universe.step();
+    }
+
+    let snapshot = universe.snapshot();
+    assert_eq!(snapshot.rule(), "B3/S23");
+    assert_eq!(snapshot.topology(), "T50,30");
+    assert_eq!(snapshot.generation(), 3);
+
+    let mut restored = Universe::from_snapshot(&snapshot).unwrap();
+    assert_eq!((restored.width(), restored.height()), (50, 30));
+    assert_eq!(restored.generation(), 3);
+    assert_eq!(live_cells(&restored), live_cells(&universe));
+    universe.step();
+    restored.step();
+    assert_eq!(live_cells(&restored), live_cells(&universe));
+
+    // an empty board is one node per level, however big it is
+    assert_eq!(Universe::new(16, 16).snapshot().nodes().len(), 3);
+    assert_eq!(Universe::new(1024, 1024).snapshot().nodes().len(), 9);
+    // and the same block everywhere is stored once
+    let mut blocks = Universe::new(64, 64);
+    for i in 0..8 {
+        blocks.set_cells(&[
+            (i * 8, i * 8),
+            (i * 8 + 1, i * 8),
+            (i * 8, i * 8 + 1),
+            (i * 8 + 1, i * 8 + 1),
+        ]);
+    }
+    let leaves = blocks
+        .snapshot()
+        .nodes()
+        .iter()
+        .filter(|node| matches!(node, SnapshotNode::Leaf(_)))
+        .count();
+    assert_eq!(leaves, 2);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+pub fn test_serde() {
+    let mut universe = Universe::new(40, 20);
+    universe.paste(
+        &library::find("glider").unwrap().pattern(),
+        (3, 3),
+        PasteMode::Or,
+    );
+    universe.step();
+
+    let json = serde_json::to_string(&universe).unwrap();
+    let restored: Universe = serde_json::from_str(&json).unwrap();
+    assert_eq!(live_cells(&restored), live_cells(&universe));
+    assert_eq!(restored.generation(), 1);
+
+    let wrong_rule = json.replace("B3/S23", "B36/S23");
+    assert!(serde_json::from_str::<Universe>(&wrong_rule).is_err());
+    let wrong_size = json.replace("T40,20", "T400,20");
+    assert!(serde_json::from_str::<Universe>(&wrong_size).is_err());
+
+    // a chain of branches doubling past the board is turned away, not overflowed
+    let mut nodes = vec![r#"{"Leaf":0}"#.to_string()];
+    nodes.extend((0..64).map(|i| format!(r#"{{"Branch":[{0},{0},{0},{0}]}}"#, i)));
+    let deep = format!(
+        r#"{{"rule":"B3/S23","topology":"T40,20","generation":0,"nodes":[{}]}}"#,
+        nodes.join(",")
+    );
+    let error = serde_json::from_str::<Universe>(&deep).unwrap_err();
+    assert!(error.to_string().contains("node is bigger than the board"));
+}
+
+#[test]
+pub fn test_session_round_trip() {
+    let mut universe = Universe::new(60, 40);
+    universe.paste(
+        &library::find("glider").unwrap().pattern(),
+        (58, 38),
+        PasteMode::Or,
+    );
+    universe.step();
+
+    let session = Session::capture(&universe, 7, 3);
+    let text = session.to_text();
+    assert!(text.starts_with("#session\nrule B3/S23\nsize 60 40\ngeneration 1\n"));
+    let restored = Session::from_text(&text).unwrap();
+    assert_eq!(restored, session);
+
+    let restored = restored.universe();
+    assert_eq!((restored.width(), restored.height()), (60, 40));
+    assert_eq!(restored.generation(), 1);
+    assert_eq!(live_cells(&restored), live_cells(&universe));
+}
+
+#[test]
+pub fn test_session_errors() {
+    assert_eq!(
+        Session::from_text("x = 1, y = 1\no!").unwrap_err(),
+        FormatError::InvalidHeader("x = 1, y = 1".to_string())
+    );
+    assert_eq!(
+        Session::from_text("#session\nrule B36/S23\nx = 1, y = 1\no!").unwrap_err(),
+        FormatError::InvalidHeader("rule B36/S23".to_string())
+    );
+    assert_eq!(
+        Session::from_text("#session\nsize 10 10").unwrap_err(),
+        FormatError::UnexpectedEnd { line: 2 }
+    );
+    assert_eq!(
+        Session::from_text("#session\nsize 100000 100000\nx = 1, y = 1\no!"),
+        Err(FormatError::TooLarge {
+            width: 100000,
+            height: 100000
+        })
+    );
+    assert_eq!(
+        Session::from_text("#session\nsize 10 10\nx = 20, y = 20\n$11o!"),
+        Err(FormatError::OutOfRange { x: 10, y: 1 })
+    );
+    // settings that aren't there get defaults, and the board fits the pattern
+    let session = Session::from_text("#session\nx = 3, y = 1\n3o!").unwrap();
+    assert_eq!(
+        (session.width, session.height, session.generation),
+        (3, 1, 0)
+    );
+}
+
+#[test]
+pub fn test_session_fragment() {
+    let mut universe = Universe::new(300, 200);
+    universe.randomize_with("share", 0.3, Some(Rect::new(100, 50, 40, 40)), Symmetry::C1);
+    let session = Session::capture(&universe, 3, 2);
+
+    let fragment = session.to_fragment();
+    assert!(fragment
+        .starts_with("rule=B3/S23&size=300x200&generation=0&cell-size=3&steps-per-tick=2&rle="));
+    assert!(fragment
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "=&/-_".contains(c)));
+    assert_eq!(Session::from_fragment(&fragment).unwrap(), session);
+    assert_eq!(
+        Session::from_fragment(&format!("#{}", fragment)).unwrap(),
+        session
+    );
+
+    assert_eq!(
+        Session::from_fragment("size=10x10"),
+        Err(FormatError::UnexpectedEnd { line: 1 })
+    );
+    assert_eq!(
+        Session::from_fragment("rule=B36/S23&rle=AA"),
+        Err(FormatError::InvalidHeader("rule=B36/S23".to_string()))
+    );
+    assert!(matches!(
+        Session::from_fragment("rle=not*base64"),
+        Err(FormatError::InvalidEncoding(_))
+    ));
+    assert!(matches!(
+        Session::from_fragment("rle=AAAA"),
+        Err(FormatError::InvalidEncoding(_))
+    ));
+
+    // boards too big to allocate are turned away before they are
+    let huge = fragment.replace("size=300x200", "size=100000x100000");
+    assert_eq!(
+        Session::from_fragment(&huge),
+        Err(FormatError::TooLarge {
+            width: 100000,
+            height: 100000
+        })
+    );
+    let compress = |rle: &str| {
+        use base64::Engine;
+        use std::io::Read;
+        let mut deflated = vec![];
+        flate2::read::DeflateEncoder::new(rle.as_bytes(), flate2::Compression::best())
+            .read_to_end(&mut deflated)
+            .unwrap();
+        let encoded = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(deflated);
+        format!("rle={}", encoded)
+    };
+    assert_eq!(
+        Session::from_fragment(&compress("x = 3000, y = 1\no!")),
+        Err(FormatError::TooLarge {
+            width: 3000,
+            height: 1
+        })
+    );
+    assert_eq!(
+        Session::from_fragment(&compress("1000000000000o!")),
+        Err(FormatError::OutOfRange { x: 2048, y: 0 })
+    );
+    let bomb = compress(&"b".repeat(20 << 20));
+    assert!(matches!(
+        Session::from_fragment(&bomb),
+        Err(FormatError::InvalidEncoding(_))
+    ));
+}
+
+#[test]
+pub fn test_plaintext_and_life_round_trip() {
+    let glider = library::find("glider").unwrap().pattern();
+    let gun = library::find("Gosper glider gun").unwrap().pattern();
+    let wide = Pattern::from_cells(&[(0, 0), (85, 3), (170, 1)]);
+
+    for pattern in &[glider, gun, wide] {
+        assert_eq!(
+            &plaintext::read(&plaintext::write(pattern)).unwrap(),
+            pattern
+        );
+        for format in &[
+            Format::Rle,
+            Format::Plaintext,
+            Format::Life105,
+            Format::Life106,
+        ] {
+            let text = format.write(pattern);
+            assert_eq!(Format::sniff(&text), *format, "{}", text);
+            let loaded = formats::load_pattern(&text).unwrap();
+            assert_eq!(&loaded.pattern, &pattern.trimmed(), "{:?}", format);
+        }
+    }
+    assert!(
+        life105::write(&Pattern::from_cells(&[(0, 0), (85, 3), (170, 1)]))
+            .lines()
+            .all(|line| line.len() <= 80)
+    );
+}
+
+#[test]
+pub fn test_load_pattern() {
+    let cells = "!Name: Glider\n!\n.O\n..O\nOOO\n";
+    let life105 = "#Life 1.05\n#D Glider\n#N\n#P -1 -1\n.*\n..*\n***\n";
+    let life106 = "#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n";
+    let headerless = "0 -1\n1 0\n-1 1\n0 1\n1 1\n";
+    assert_eq!(Format::sniff(cells), Format::Plaintext);
+    assert_eq!(Format::sniff(life105), Format::Life105);
+    assert_eq!(Format::sniff(life106), Format::Life106);
+    assert_eq!(Format::sniff(headerless), Format::Life106);
+    assert_eq!(Format::sniff("#N Glider\nbo$2bo$3o!"), Format::Rle);
+    assert_eq!(Format::sniff("2bo$3o!"), Format::Rle);
+
+    let expected = vec![(11, 10), (12, 11), (10, 12), (11, 12), (12, 12)];
+    for (text, at) in &[
+        (cells, (10, 10)),
+        (life105, (11, 11)),
+        (life106, (11, 11)),
+        (headerless, (11, 11)),
+    ] {
+        let mut universe = Universe::new(32, 32);
+        universe.load_pattern(text, *at).unwrap();
+        assert_eq!(live_cells(&universe), expected, "{}", text);
+    }
+
+    let mut universe = Universe::new(32, 32);
+    assert_eq!(
+        universe.load_pattern(life106, (0, 0)),
+        Err(FormatError::OutOfRange { x: 0, y: -1 })
+    );
+    assert_eq!(
+        universe.load_pattern(cells, (30, 0)),
+        Err(FormatError::OutOfRange { x: 32, y: 1 })
+    );
+    // RLE stops at the edge of the board while it's read, long runs and all
+    assert_eq!(
+        universe.load_pattern("x = 1000000000, y = 1\n1000000000o!", (20, 3)),
+        Err(FormatError::OutOfRange { x: 32, y: 3 })
+    );
+    assert_eq!(
+        universe.load_pattern("bo$2bo$3o!", (-1, 0)),
+        Err(FormatError::OutOfRange { x: -1, y: 2 })
+    );
+    assert_eq!(
+        universe.load_pattern("bo$2bo$3o!", (0, 30)),
+        Err(FormatError::OutOfRange { x: 0, y: 32 })
+    );
+    assert_eq!(universe.population(), 0);
+    universe.load_pattern("bo$2bo$3o!", (10, 10)).unwrap();
+    assert_eq!(live_cells(&universe), expected);
+}
+
+#[test]
+pub fn test_plaintext_and_life_errors() {
+    assert_eq!(
+        plaintext::read("!Name\n.O\n.o\n"),
+        Err(FormatError::UnexpectedCharacter {
+            character: 'o',
+            line: 3
+        })
+    );
+    assert_eq!(
+        life106::read("#Life 1.06\n1 2\n3\n"),
+        Err(FormatError::UnexpectedEnd { line: 3 })
+    );
+    assert_eq!(
+        life106::read("#Life 1.06\n1 x\n"),
+        Err(FormatError::UnexpectedCharacter {
+            character: 'x',
+            line: 2
+        })
+    );
+    assert_eq!(
+        life106::read("#Life 1.06\n1 99999999999999999999\n"),
+        Err(FormatError::NumberTooLarge { line: 2 })
+    );
+    assert_eq!(
+        life105::read("#Life 1.05\n#R 34/34\n*\n"),
+        Err(FormatError::InvalidHeader("#R 34/34".to_string()))
+    );
+    assert_eq!(
+        life105::read("#Life 1.05\n#P 0\n*\n"),
+        Err(FormatError::UnexpectedEnd { line: 2 })
+    );
+    assert_eq!(
+        life105::read("#Life 1.05\n#P 0 0\n*O\n"),
+        Err(FormatError::UnexpectedCharacter {
+            character: 'O',
+            line: 3
+        })
+    );
+    // coordinates that don't fit in an `isize` once offset are out of range
+    assert_eq!(
+        life106::read("#Life 1.06\n-9223372036854775808 0\n9223372036854775807 0\n"),
+        Err(FormatError::OutOfRange {
+            x: 9223372036854775807,
+            y: 0
+        })
+    );
+    assert_eq!(
+        life105::read("#Life 1.05\n#P 9223372036854775806 0\n.**\n"),
+        Err(FormatError::OutOfRange {
+            x: isize::MAX,
+            y: 0
+        })
+    );
+    assert_eq!(
+        life105::read("#Life 1.05\n#P 0 9223372036854775807\n*\n*\n"),
+        Err(FormatError::OutOfRange {
+            x: 0,
+            y: isize::MAX
+        })
+    );
+    let mut universe = Universe::new(32, 32);
+    assert_eq!(
+        universe.load_pattern("#Life 1.06\n9223372036854775807 0\n", (10, 10)),
+        Err(FormatError::OutOfRange {
+            x: isize::MAX,
+            y: 10
+        })
+    );
+    assert_eq!(
+        universe.load_pattern("#Life 1.05\n#P -9223372036854775808 0\n*\n", (-1, 0)),
+        Err(FormatError::OutOfRange {
+            x: isize::MAX,
+            y: 0
+        })
+    );
+    assert_eq!(universe.population(), 0);
+    let loaded = life105::read("#Life 1.05\n#R 23/3\n#P -5 2\n.*\n").unwrap();
+    assert_eq!(loaded.origin, (-4, 2));
+    assert_eq!(loaded.pattern.population(), 1);
+}
+
+#[test]
+pub fn test_render() {
+    let mut universe = Universe::new(8, 6);
+    universe.set_cells(&[(1, 1), (2, 1), (7, 5)]);
+
+    let options = RenderOptions {
+        cell_size: 3,
+        ..RenderOptions::default()
+    };
+    let image = render::render_universe(&universe, &options);
+    assert_eq!((image.width(), image.height()), (8 * 4 + 1, 6 * 4 + 1));
+    assert_eq!(image.pixels().len(), image.width() * image.height() * 4);
+    assert_eq!(image.get_pixel(0, 0), GRID_COLOR);
+    assert_eq!(image.get_pixel(4, 2), GRID_COLOR);
+    assert_eq!(image.get_pixel(1, 1), DEAD_COLOR);
+    assert_eq!(image.get_pixel(5, 5), ALIVE_COLOR);
+    assert_eq!(image.get_pixel(7, 7), ALIVE_COLOR);
+    assert_eq!(image.get_pixel(8, 5), GRID_COLOR);
+    assert_eq!(image.get_pixel(9, 5), ALIVE_COLOR);
+    assert_eq!(image.get_pixel(31, 23), ALIVE_COLOR);
+    assert_eq!(image.get_pixel(32, 24), GRID_COLOR);
+
+    // a region, without the grid and in other colors
+    let options = RenderOptions {
+        cell_size: 2,
+        grid: false,
+        alive: Color::rgb(0xFF, 0, 0),
+        dead: Color::rgba(0, 0, 0, 0),
+        ..RenderOptions::default()
+    };
+    let image = render::render(&universe, Rect::new(1, 1, 2, 2), &options);
+    assert_eq!((image.width(), image.height()), (4, 4));
+    let reds = image
+        .pixels()
+        .chunks(4)
+        .filter(|pixel| pixel == &[0xFF, 0, 0, 0xFF])
+        .count();
+    assert_eq!(reds, 8);
+    assert_eq!(image.get_pixel(0, 2), Color::rgba(0, 0, 0, 0));
+
+    let png = image.to_png();
+    let mut decoder = png::Decoder::new(&png[..]).read_info().unwrap();
+    let mut pixels = vec![0; decoder.output_buffer_size()];
+    let info = decoder.next_frame(&mut pixels).unwrap();
+    assert_eq!((info.width, info.height), (4, 4));
+    assert_eq!(info.color_type, png::ColorType::Rgba);
+    assert_eq!(&pixels[..info.buffer_size()], image.pixels());
+}
+
+#[test]
+pub fn test_colors() {
+    assert_eq!("#CCCCCC".parse(), Ok(GRID_COLOR));
+    assert_eq!("fff".parse(), Ok(DEAD_COLOR));
+    assert_eq!("#ff000080".parse(), Ok(Color::rgba(0xFF, 0, 0, 0x80)));
+    assert!("#ff00".parse::<Color>().is_err());
+    assert!("#gggggg".parse::<Color>().is_err());
+    assert_eq!(ALIVE_COLOR.css(), "#000000");
+    assert_eq!(Color::rgba(1, 2, 3, 4).to_string(), "#01020304");
+}
+
+#[test]
+pub fn test_animation() {
+    let glider = library::find("glider").unwrap().pattern();
+    let mut universe = Universe::new(64, 64);
+    universe.paste(&glider, (10, 10), PasteMode::Copy);
+    let before = live_cells(&universe);
+
+    let options = AnimationOptions {
+        frames: 9,
+        step: 4,
+        delay: 50,
+        crop: Crop::Follow { margin: 1 },
+        render: RenderOptions {
+            cell_size: 1,
+            grid: false,
+            ..RenderOptions::default()
+        },
+    };
+    let frames = animation::frames(&universe, &options);
+    assert_eq!(live_cells(&universe), before);
+    assert_eq!(universe.generation(), 0);
+
+    // every fourth generation the glider is back in the same phase, one cell further on,
+    // so following it every frame looks the same
+    assert_eq!(frames.len(), 9);
+    assert_eq!((frames[0].width(), frames[0].height()), (5, 5));
+    assert!(frames.iter().all(|frame| frame == &frames[0]));
+
+    let fixed = AnimationOptions {
+        crop: Crop::Region(Rect::new(8, 8, 20, 20)),
+        ..options
+    };
+    let frames = animation::frames(&universe, &fixed);
+    assert_eq!((frames[8].width(), frames[8].height()), (20, 20));
+    assert_ne!(frames[0], frames[8]);
+    assert_eq!(frames[8].get_pixel(2 + 8 + 1, 2 + 8 + 2), ALIVE_COLOR);
+
+    let gif = animation::animate(&universe, &fixed).unwrap();
+    let mut decoder = gif::DecodeOptions::new();
+    decoder.set_color_output(gif::ColorOutput::RGBA);
+    let mut decoder = decoder.read_info(&gif[..]).unwrap();
+    let mut count = 0;
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        assert_eq!((frame.width, frame.height), (20, 20));
+        assert_eq!(frame.delay, 5);
+        assert_eq!(&frame.buffer[..], frames[count].pixels());
+        count += 1;
+    }
+    assert_eq!(count, 9);
+
+    assert!(animation::to_gif(&[], 10).is_err());
+    let mixed = [frames[0].clone(), render::Image::new(3, 3, DEAD_COLOR)];
+    assert!(animation::to_gif(&mixed, 10).is_err());
+}
+
+/// the cells covered by the live cell rectangles of an svg, drawn with a grid
+fn svg_cells(svg: &str, cell_size: usize) -> Vec<(usize, usize)> {
+    let start = svg.find("<g fill=\"#000000\">").unwrap();
+    let end = start + svg[start..].find("</g>").unwrap();
+    let number = |line: &str, name: &str| -> usize {
+        let start = line.find(&format!(" {}=\"", name)).unwrap() + name.len() + 3;
+        let end = start + line[start..].find('"').unwrap();
+        line[start..end].parse().unwrap()
+    };
+
+    let pitch = cell_size + 1;
+    let mut cells = vec![];
+    for line in svg[start..end]
+        .lines()
+        .filter(|line| line.starts_with("<rect"))
+    {
+        let (x, y) = (
+            (number(line, "x") - 1) / pitch,
+            (number(line, "y") - 1) / pitch,
+        );
+        let width = (number(line, "width") + 1) / pitch;
+        let height = (number(line, "height") + 1) / pitch;
+        for cy in y..y + height {
+            for cx in x..x + width {
+                cells.push((cx, cy));
+            }
+        }
+    }
+    cells.sort_by_key(|&(x, y)| (y, x));
+    cells
+}
+
+#[test]
+pub fn test_svg() {
+    let mut universe = Universe::new(16, 16);
+    let block = Pattern::from_cells(&[(0, 0), (1, 0), (0, 1), (1, 1)]);
+    universe.paste(&block, (1, 1), PasteMode::Or);
+    universe.paste(
+        &library::find("glider").unwrap().pattern(),
+        (6, 6),
+        PasteMode::Or,
+    );
+    universe.set_cells(&[(10, 1), (11, 1), (12, 1), (13, 1)]);
+
+    let options = SvgOptions {
+        render: RenderOptions {
+            cell_size: 4,
+            ..RenderOptions::default()
+        },
+        ..SvgOptions::default()
+    };
+    let whole = Rect::new(0, 0, 16, 16);
+    let drawn = svg::to_svg(&universe, whole, &options);
+    assert!(
+        drawn.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"81\" height=\"81\"")
+    );
+    assert!(drawn.trim_end().ends_with("</svg>"));
+    assert_eq!(svg_cells(&drawn, 4), live_cells(&universe));
+    // the block and the line are a rectangle each, the glider takes three
+    assert_eq!(drawn.matches("<rect x=").count(), 5);
+    assert!(drawn.contains("stroke=\"#CCCCCC\""));
+    assert!(!drawn.contains("<text"));
+
+    let options = SvgOptions {
+        render: RenderOptions {
+            grid: false,
+            ..options.render
+        },
+        labels: Some(5),
+        highlights: vec![Highlight::new(Rect::new(5, 5, 5, 5))],
+    };
+    let drawn = svg::to_svg(&universe, Rect::new(4, 4, 8, 8), &options);
+    assert!(!drawn.contains("<path"));
+    assert!(drawn.contains(">5</text>") && drawn.contains(">10</text>"));
+    assert!(!drawn.contains(">0</text>"));
+    assert!(drawn.contains("fill=\"#FFD700\" fill-opacity=\"0.376\""));
+    assert_eq!(drawn.matches("<rect x=").count(), 3 + 1);
+}
+
+#[test]
+pub fn test_history() {
+    let mut universe = Universe::new(16, 16);
+    universe.set_cells(&[(4, 5), (5, 5), (6, 5)]);
+    assert!(!universe.is_tracking_history());
+    assert!(universe.envelope_cells().is_none());
+    assert_eq!(universe.history_rle(), None);
+
+    universe.set_tracking_history(true);
+    assert_eq!(universe.envelope_population(), Some(3));
+    universe.step();
+    universe.step();
+    // a blinker's envelope is a plus
+    let mut envelope: Vec<_> = universe.envelope_cells().unwrap().collect();
+    envelope.sort_by_key(|&(x, y)| (y, x));
+    assert_eq!(envelope, vec![(5, 4), (4, 5), (5, 5), (6, 5), (5, 6)]);
+    assert_eq!(
+        universe.envelope_bounding_box(),
+        Some(Rect::new(4, 4, 3, 3))
+    );
+
+    // edits go in the envelope too, clearing cells doesn't take them out
+    universe.toggle_cell(10, 10);
+    universe.clear_region(Rect::new(10, 10, 1, 1));
+    assert_eq!(universe.envelope_population(), Some(6));
+
+    let envelope = universe.copy_envelope(Rect::new(4, 4, 7, 7)).unwrap();
+    assert_eq!(envelope.population(), 6);
+    assert!(envelope.get_cell(6, 6));
+
+    let rle = universe.history_rle().unwrap();
+    assert!(rle.starts_with("x = 16, y = 16, rule = LifeHistory\n"));
+    assert!(rle.contains("5.B$4.3A$5.B"), "{}", rle);
+    // the live cells come back out of it
+    assert_eq!(rle::read(&rle).unwrap().cells(), &[(4, 5), (5, 5), (6, 5)]);
+
+    // the envelope moves with the board
+    universe.resize(20, 20, Anchor::BottomRight);
+    assert_eq!(
+        universe.envelope_bounding_box(),
+        Some(Rect::new(8, 8, 7, 7))
+    );
+
+    universe.clear_history();
+    assert_eq!(universe.envelope_population(), Some(3));
+    universe.reset();
+    assert!(universe.is_tracking_history());
+    assert_eq!(universe.envelope_population(), Some(0));
+    universe.set_tracking_history(false);
+    assert_eq!(universe.envelope_population(), None);
+}
+
+#[test]
+pub fn test_render_history() {
+    let mut universe = Universe::new(8, 8);
+    universe.set_cells(&[(1, 2), (2, 2), (3, 2)]);
+    universe.set_tracking_history(true);
+    universe.step();
+
+    let options = RenderOptions {
+        cell_size: 1,
+        grid: false,
+        ..RenderOptions::default()
+    };
+    let image = render::render_universe(&universe, &options);
+    assert_eq!(image.get_pixel(2, 1), ALIVE_COLOR);
+    assert_eq!(image.get_pixel(1, 2), ENVELOPE_COLOR);
+    assert_eq!(image.get_pixel(0, 0), DEAD_COLOR);
+}
+
+#[test]
+pub fn test_heatmap() {
+    let mut universe = Universe::new(12, 10);
+    universe.set_cells(&[(4, 5), (5, 5), (6, 5)]);
+    universe.paste(
+        &Pattern::from_cells(&[(0, 0), (1, 0), (0, 1), (1, 1)]),
+        (9, 1),
+        PasteMode::Or,
+    );
+    assert!(universe.activity().is_none());
+
+    universe.set_tracking_activity(Some(100));
+    for _ in 0..4 {
+        universe.step();
+    }
+    let heatmap = universe.activity().unwrap();
+    assert_eq!((heatmap.width(), heatmap.height()), (12, 10));
+    assert_eq!(heatmap.generations(), 4);
+    // the ends of the blinker flip every generation, its middle and the block never do
+    for &(x, y) in &[(4, 5), (6, 5), (5, 4), (5, 6)] {
+        assert_eq!(heatmap.get(x, y), 4);
+    }
+    assert_eq!(heatmap.get(5, 5), 0);
+    assert_eq!(heatmap.get(9, 1), 0);
+    assert_eq!(heatmap.max(), 4);
+    assert_eq!(heatmap.active_cells().count(), 4);
+
+    let csv = heatmap.to_csv();
+    assert_eq!(csv.lines().count(), 10);
+    assert_eq!(csv.lines().nth(5), Some("0,0,0,0,4,0,4,0,0,0,0,0"));
+
+    // a smaller window forgets the oldest generations
+    universe.set_tracking_activity(Some(3));
+    assert_eq!(universe.activity().unwrap().get(4, 5), 3);
+    universe.step();
+    universe.step();
+    assert_eq!(universe.activity().unwrap().generations(), 3);
+    assert_eq!(universe.activity().unwrap().get(5, 4), 3);
+
+    // edits aren't activity, and moving the board starts over
+    universe.toggle_cell(0, 0);
+    assert_eq!(universe.activity().unwrap().get(0, 0), 0);
+    universe.resize(14, 10, Anchor::TopRight);
+    assert_eq!(universe.activity().unwrap().width(), 14);
+    assert_eq!(universe.activity().unwrap().max(), 0);
+
+    universe.set_tracking_activity(None);
+    assert!(universe.activity().is_none());
+
+    assert_eq!(render::heat_color(0, 4).a, 0);
+    assert!(render::heat_color(1, 4).a < render::heat_color(4, 4).a);
+    assert_eq!(render::heat_color(9, 4), render::heat_color(4, 4));
+}
+
+#[test]
+pub fn test_diff() {
+    let mut universe = Universe::new(40, 30);
+    universe.paste(
+        &library::find("glider").unwrap().pattern(),
+        (5, 5),
+        PasteMode::Or,
+    );
+    universe.set_cells(&[(30, 20), (31, 20), (30, 21), (31, 21)]);
+
+    for _ in 0..8 {
+        let before_cells = live_cells(&universe);
+        let before = universe.root();
+        universe.step();
+        let after_cells = live_cells(&universe);
+
+        let diff = universe.diff(before, universe.root());
+        let births: Vec<_> = after_cells
+            .iter()
+            .filter(|cell| !before_cells.contains(cell))
+            .copied()
+            .collect();
+        let deaths: Vec<_> = before_cells
+            .iter()
+            .filter(|cell| !after_cells.contains(cell))
+            .copied()
+            .collect();
+        assert_eq!(diff.births(), &births[..]);
+        assert_eq!(diff.deaths(), &deaths[..]);
+        assert_eq!(diff.len(), births.len() + deaths.len());
+
+        // the block never changes, so nothing near it does either
+        let bounds = diff.bounding_box().unwrap();
+        assert!(bounds.right() < 30 && bounds.bottom() < 20);
+
+        let covered: usize = diff.rects().iter().map(|r| r.width() * r.height()).sum();
+        assert_eq!(covered, diff.len());
+        for r in diff.birth_rects() {
+            for y in r.y()..r.bottom() {
+                for x in r.x()..r.right() {
+                    assert!(diff.births().contains(&(x, y)));
+                }
+            }
+        }
+    }
+
+    // the other way round swaps births and deaths
+    let before = universe.root();
+    universe.step();
+    let forward = universe.diff(before, universe.root());
+    let backward = universe.diff(universe.root(), before);
+    assert_eq!(forward.births(), backward.deaths());
+    assert_eq!(forward.deaths(), backward.births());
+    assert!(universe.diff(before, before).is_empty());
+    assert_eq!(universe.diff(before, before).bounding_box(), None);
+}
+
+#[test]
+pub fn test_cover() {
+    let mut cells = vec![
+        (1, 1),
+        (2, 1),
+        (1, 2),
+        (2, 2),
+        (5, 1),
+        (6, 1),
+        (7, 1),
+        (6, 2),
+    ];
+    let rects = rect::cover(&mut cells);
+    assert_eq!(
+        rects,
+        vec![
+            Rect::new(1, 1, 2, 2),
+            Rect::new(5, 1, 3, 1),
+            Rect::new(6, 2, 1, 1)
+        ]
+    );
+    assert!(rect::cover(&mut []).is_empty());
+}
+
+#[test]
+pub fn test_frame_updates() {
+    let mut universe = Universe::new(48, 40);
+    universe.paste(
+        &library::find("glider").unwrap().pattern(),
+        (3, 3),
+        PasteMode::Or,
+    );
+    universe.set_cells(&[(40, 30), (41, 30), (42, 30)]);
+
+    let options = RenderOptions {
+        cell_size: 3,
+        ..RenderOptions::default()
+    };
+    let mut frame = Frame::new(48, 40, options);
+    let whole = Rect::new(0, 0, 48 * 4 + 1, 40 * 4 + 1);
+    assert_eq!(frame.update(&universe), vec![whole]);
+    assert_eq!(frame.image(), &render::render_universe(&universe, &options));
+    assert!(frame.update(&universe).is_empty());
+
+    for generation in 0..12 {
+        if generation == 4 {
+            universe.set_tracking_history(true);
+        }
+        if generation == 8 {
+            universe.toggle_cell(20, 20);
+        }
+        let before = frame.image().clone();
+        universe.step();
+        let dirty = frame.update(&universe);
+        let expected = render::render_universe(&universe, &options);
+        assert_eq!(frame.image(), &expected, "generation {}", generation);
+
+        // nothing outside of the repainted rectangles changed
+        for y in 0..expected.height() {
+            for x in 0..expected.width() {
+                if before.get_pixel(x, y) != expected.get_pixel(x, y) {
+                    assert!(
+                        dirty.iter().any(|rect| rect.contains(x, y)),
+                        "({}, {})",
+                        x,
+                        y
+                    );
+                }
+            }
+        }
+        // the blinker and the glider are all that move, a few cells each
+        if generation != 4 {
+            assert!(dirty.len() <= MAX_DIRTY_RECTS);
+            assert!(dirty
+                .iter()
+                .all(|rect| rect.width() < 60 && rect.height() < 60));
+        }
+    }
+
+    // a different size starts over
+    universe.resize(50, 40, Anchor::TopLeft);
+    assert_eq!(
+        frame.update(&universe),
+        vec![Rect::new(0, 0, 50 * 4 + 1, 40 * 4 + 1)]
+    );
+    frame.invalidate();
+    assert_eq!(frame.update(&universe).len(), 1);
+    assert_eq!(frame.image(), &render::render_universe(&universe, &options));
+
+    let image = frame.image();
+    let pixels = image.region_pixels(Rect::new(1, 2, 3, 2));
+    assert_eq!(pixels.len(), 3 * 2 * 4);
+    assert_eq!(&pixels[12..16], &image.get_pixel(1, 3).to_rgba());
+}
+
+#[test]
+pub fn test_frame_overlay() {
+    let half_red = Color::rgba(255, 0, 0, 128);
+    assert_eq!(
+        half_red.over(Color::rgb(0, 0, 255)),
+        Color::rgb(128, 0, 127)
+    );
+    assert_eq!(ALIVE_COLOR.over(half_red), ALIVE_COLOR);
+    assert_eq!(Color::rgba(0, 0, 0, 0).over(half_red), half_red);
+
+    let mut universe = Universe::new(16, 16);
+    universe.set_cells(&[(4, 5), (5, 5), (6, 5)]);
+    let options = RenderOptions {
+        cell_size: 3,
+        ..RenderOptions::default()
+    };
+    let mut frame = Frame::new(16, 16, options);
+    frame.update(&universe);
+    let plain = frame.image().clone();
+
+    // only the cells under a new overlay are repainted
+    let mut overlay = Overlay::new();
+    overlay.insert((1, 1), half_red);
+    assert_eq!(
+        frame.update_with_overlay(&universe, overlay.clone()),
+        vec![Rect::new(5, 5, 3, 3)]
+    );
+    assert_eq!(frame.image().get_pixel(5, 5), half_red.over(DEAD_COLOR));
+    assert_eq!(frame.image().get_pixel(9, 5), plain.get_pixel(9, 5));
+    assert!(frame
+        .update_with_overlay(&universe, overlay.clone())
+        .is_empty());
+
+    // the board changing under the overlay leaves it on top
+    overlay.insert((5, 4), half_red);
+    universe.step();
+    let dirty = frame.update_with_overlay(&universe, overlay.clone());
+    assert!(dirty
+        .iter()
+        .all(|rect| rect.width() < 20 && rect.height() < 20));
+    let (x, y) = options.cell_origin(5, 4);
+    assert_eq!(frame.image().get_pixel(x, y), half_red.over(ALIVE_COLOR));
+    let (x, y) = options.cell_origin(4, 5);
+    assert_eq!(frame.image().get_pixel(x, y), DEAD_COLOR);
+
+    // and so does a full redraw
+    frame.invalidate();
+    frame.update_with_overlay(&universe, overlay);
+    assert_eq!(frame.image().get_pixel(5, 5), half_red.over(DEAD_COLOR));
+
+    // taking it away puts back the board as it is
+    assert_eq!(
+        frame.update(&universe),
+        vec![Rect::new(5, 5, 3, 3), Rect::new(21, 17, 3, 3)]
+    );
+    assert_eq!(frame.image(), &render::render_universe(&universe, &options));
+}
+
+#[test]
+pub fn test_cell_ages() {
+    let mut universe = Universe::new(32, 32);
+    universe.set_cells(&[(4, 5), (5, 5), (6, 5)]);
+    universe.paste(
+        &Pattern::from_cells(&[(0, 0), (1, 0), (0, 1), (1, 1)]),
+        (20, 20),
+        PasteMode::Or,
+    );
+    assert!(universe.ages().is_none());
+
+    // the viewport is clipped to the board
+    universe.set_tracking_ages(Some(Rect::new(0, 0, 24, 40)));
+    assert_eq!(universe.ages().unwrap().viewport(), Rect::new(0, 0, 24, 32));
+    assert_eq!(universe.ages().unwrap().get(4, 5), Some(0));
+
+    for _ in 0..5 {
+        universe.step();
+    }
+    let ages = universe.ages().unwrap();
+    // the middle of the blinker never dies, its ends are reborn every other generation
+    assert_eq!(ages.get(5, 5), Some(5));
+    assert_eq!(ages.get(5, 4), Some(0));
+    assert_eq!(ages.get(4, 5), None);
+    assert_eq!(ages.get(21, 21), Some(5));
+    assert_eq!(ages.max(), Some(5));
+    assert_eq!(ages.cells().count(), 7);
+
+    // edits count as births, outside the viewport nothing is kept
+    universe.toggle_cell(10, 10);
+    universe.toggle_cell(30, 30);
+    assert_eq!(universe.ages().unwrap().get(10, 10), Some(0));
+    assert_eq!(universe.ages().unwrap().get(30, 30), None);
+    universe.clear_region(Rect::new(20, 20, 2, 2));
+    assert_eq!(universe.ages().unwrap().get(21, 21), None);
+
+    universe.resize(40, 32, Anchor::TopRight);
+    assert_eq!(universe.ages().unwrap().get(13, 5), Some(0));
+
+    universe.reset();
+    assert_eq!(universe.ages().unwrap().max(), None);
+    universe.set_tracking_ages(None);
+    assert!(universe.ages().is_none());
+}
+
+#[test]
+pub fn test_render_by_age() {
+    let mut universe = Universe::new(16, 16);
+    universe.set_cells(&[(4, 5), (5, 5), (6, 5)]);
+    universe.set_tracking_ages(Some(Rect::new(0, 0, 16, 16)));
+
+    let options = RenderOptions {
+        cell_size: 1,
+        grid: false,
+        color_by_age: true,
+        ..RenderOptions::default()
+    };
+    let mut frame = Frame::new(16, 16, options);
+    frame.update(&universe);
+    for _ in 0..3 {
+        universe.step();
+        assert_eq!(frame.update(&universe).len(), 1);
+        assert_eq!(frame.image(), &render::render_universe(&universe, &options));
+    }
+    assert!(frame.update(&universe).is_empty());
+
+    let image = frame.image();
+    assert_eq!(image.get_pixel(5, 5), render::age_color(3));
+    assert_eq!(image.get_pixel(5, 4), render::age_color(0));
+    assert_ne!(render::age_color(0), render::age_color(3));
+    assert_eq!(
+        render::age_color(render::OLD_AGE),
+        render::age_color(10_000)
+    );
+
+    // without ages being kept the live cells are just alive
+    universe.set_tracking_ages(None);
+    frame.update(&universe);
+    assert_eq!(frame.image().get_pixel(5, 5), ALIVE_COLOR);
+}
+
+#[test]
+pub fn test_timeline() {
+    let mut universe = Universe::new(32, 32);
+    universe.set_cells(&[(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)]);
+    let mut timeline = Timeline::new(4, 3);
+    timeline.record(&universe);
+
+    let mut roots = vec![universe.root()];
+    for _ in 0..10 {
+        universe.step();
+        timeline.record(&universe);
+        roots.push(universe.root());
+    }
+    let generations = |timeline: &Timeline| -> Vec<usize> {
+        timeline
+            .checkpoints()
+            .iter()
+            .map(|c| c.generation)
+            .collect()
+    };
+    assert_eq!(generations(&timeline), vec![0, 4, 8]);
+    assert_eq!(timeline.range(), Some((0, 10)));
+
+    // going back re-simulates from the checkpoint before, going forward again too
+    timeline.seek(&mut universe, 6).unwrap();
+    assert_eq!(universe.generation(), 6);
+    assert_eq!(universe.root(), roots[6]);
+    timeline.step_back(&mut universe).unwrap();
+    assert_eq!(universe.root(), roots[5]);
+    timeline.seek(&mut universe, 10).unwrap();
+    assert_eq!(universe.root(), roots[10]);
+    assert_eq!(timeline.range(), Some((0, 10)));
+    assert!(timeline.seek(&mut universe, 0).is_ok());
+    assert!(timeline.step_back(&mut universe).is_err());
+
+    // past the limit every other checkpoint goes and the interval doubles
+    timeline.seek(&mut universe, 10).unwrap();
+    for _ in 0..2 {
+        universe.step();
+        timeline.record(&universe);
+    }
+    assert_eq!(generations(&timeline), vec![0, 4, 12]);
+    assert_eq!(timeline.interval(), 8);
+    timeline.seek(&mut universe, 9).unwrap();
+    assert_eq!(universe.root(), roots[9]);
+
+    // an edit in the past branches off, forgetting what came after
+    timeline.seek(&mut universe, 3).unwrap();
+    universe.toggle_cell(20, 20);
+    timeline.record(&universe);
+    assert_eq!(generations(&timeline), vec![0, 3]);
+    assert!(timeline.checkpoints()[1].edited);
+    assert_eq!(timeline.range(), Some((0, 3)));
+    universe.step();
+    universe.step();
+    timeline.record(&universe);
+    timeline.seek(&mut universe, 3).unwrap();
+    assert_eq!(universe.get_cell(20, 20), Ok(true));
+    timeline.seek(&mut universe, 2).unwrap();
+    assert_eq!(universe.root(), roots[2]);
+
+    // stepping on from before the edit leaves it behind
+    universe.step();
+    timeline.record(&universe);
+    assert_eq!(universe.root(), roots[3]);
+    assert_eq!(generations(&timeline), vec![0]);
+    assert_eq!(timeline.range(), Some((0, 3)));
+
+    // a different board size starts over
+    universe.resize(40, 32, Anchor::TopLeft);
+    timeline.record(&universe);
+    assert_eq!(generations(&timeline), vec![3]);
+    assert!(timeline.seek(&mut universe, 1).is_err());
+}